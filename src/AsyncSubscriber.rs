@@ -0,0 +1,68 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// An asynchronous subscriber to a queue, suitable for use inside a per-core async executor.
+///
+/// Create using `Queues::async_subscriber()`.
+///
+/// Not thread safe; one instance per thread is required.
+///
+/// Instead of polling in a loop, call `poll_receive()` from a `Future` or `Stream`; when there are no messages, the task's `Waker` is registered and is woken by the first message published after the queue became empty.
+/// Publishers do not wake the task (or take any lock) unless a `Waker` is registered.
+#[derive(Debug)]
+pub struct AsyncSubscriber<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>
+{
+	subscriber: Subscriber<MessageHandlerArguments, DequeuedMessageProcessingError>,
+}
+
+impl<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> AsyncSubscriber<MessageHandlerArguments, DequeuedMessageProcessingError>
+{
+	#[inline(always)]
+	fn new(queues: &Queues<MessageHandlerArguments, DequeuedMessageProcessingError>, for_hyper_thread: HyperThread) -> Self
+	{
+		Self
+		{
+			subscriber: Subscriber::new(queues, for_hyper_thread),
+		}
+	}
+	
+	/// Receives and handles messages if there are any pending, returning `Poll::Ready`.
+	///
+	/// Otherwise registers the waker in `context` and returns `Poll::Pending`.
+	/// The waker is not woken when a message in the timer wheel becomes due; use `next_scheduled_message_due_at()` to arm a timer.
+	///
+	/// Once `Poll::Ready` has been returned, call again to receive further messages; there is no end of stream.
+	/// Message handling short-circuits as for `Subscriber::receive_and_handle_messages()`; a `dequeue_budget` lets the task yield to others in the executor.
+	#[inline(always)]
+	pub fn poll_receive(&self, context: &mut Context, terminate: &Arc<impl Terminate>, message_handler_arguments: &MessageHandlerArguments, dequeue_budget: &DequeueBudget, dequeued_message_processing_error_policy: &mut DequeuedMessageProcessingErrorPolicy<DequeuedMessageProcessingError>) -> Poll<Result<DequeueOutcome, DequeueFailed<DequeuedMessageProcessingError>>>
+	{
+		let queue = self.subscriber.queue();
+		
+		if !queue.has_pending_messages() && !queue.has_due_scheduled_messages()
+		{
+			queue.register_subscriber_waker(context.waker());
+			
+			if !queue.has_pending_messages()
+			{
+				return Poll::Pending
+			}
+		}
+		
+		Poll::Ready(self.subscriber.receive_and_handle_messages(terminate, message_handler_arguments, dequeue_budget, dequeued_message_processing_error_policy))
+	}
+	
+	/// See `Subscriber::next_scheduled_message_due_at()`.
+	#[inline(always)]
+	pub fn next_scheduled_message_due_at(&self) -> Option<MonotonicTime>
+	{
+		self.subscriber.next_scheduled_message_due_at()
+	}
+	
+	/// See `Subscriber::take_message_handler_panics()`.
+	#[inline(always)]
+	pub fn take_message_handler_panics(&self) -> Vec<HandlerPanicked>
+	{
+		self.subscriber.take_message_handler_panics()
+	}
+}
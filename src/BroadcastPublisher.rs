@@ -0,0 +1,94 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A broadcast publisher, which publishes a message to every one of a set (`hyper_threads_to_publish_to`) of `HyperThread`s, each of which has a queue.
+///
+/// Each hyper thread receives its own message, constructed from a clone of the `ConstructMessageArguments` (or a clone of the message).
+/// Suitable for configuration reloads and cache invalidations.
+#[derive(Debug)]
+pub struct BroadcastPublisher<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>
+{
+	publisher: Publisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>,
+	hyper_threads_to_publish_to: Box<[HyperThread]>,
+}
+
+impl<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> BroadcastPublisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>
+{
+	#[inline(always)]
+	fn new(queues: &Queues<MessageHandlerArguments, DequeuedMessageProcessingError>, hyper_threads_to_publish_to: Box<[HyperThread]>) -> Self
+	{
+		queues.assert_hyper_threads_to_publish_to_have_queues(&hyper_threads_to_publish_to);
+		for (index, hyper_thread) in hyper_threads_to_publish_to.iter().enumerate()
+		{
+			assert!(!hyper_threads_to_publish_to[(index + 1) .. ].contains(hyper_thread), "Hyper thread `{:?}` would receive each message more than once", hyper_thread)
+		}
+		let default_hyper_thread = hyper_threads_to_publish_to.get_unchecked_value_safe(0);
+		
+		Self
+		{
+			publisher: Publisher::new(queues, default_hyper_thread),
+			hyper_threads_to_publish_to,
+		}
+	}
+	
+	/// Publishes to every hyper thread, constructing each message from a clone of `construct_message_arguments`.
+	///
+	/// The last hyper thread receives `construct_message_arguments` itself.
+	#[inline(always)]
+	pub fn publish(&self, construct_message_arguments: M::ConstructMessageArguments) where M::ConstructMessageArguments: Clone
+	{
+		self.broadcast(construct_message_arguments, |hyper_thread, construct_message_arguments| { self.publisher.publish(hyper_thread, construct_message_arguments); })
+	}
+	
+	/// Publishes a clone of `message` to every hyper thread.
+	///
+	/// The last hyper thread receives `message` itself.
+	#[inline(always)]
+	pub fn publish_value(&self, message: M) where M: Clone
+	{
+		self.broadcast(message, |hyper_thread, message| { self.publisher.publish_value(hyper_thread, message); })
+	}
+	
+	/// Publishes to every hyper thread without waiting for space in their queues, constructing each message from a clone of `construct_message_arguments`.
+	///
+	/// A full queue does not stop publishing to the remaining hyper threads.
+	///
+	/// Returns a `PublishError::QueueFull` for each hyper thread whose queue was full, which hands back the unconsumed clone of `construct_message_arguments`.
+	#[inline(always)]
+	pub fn try_publish(&self, construct_message_arguments: M::ConstructMessageArguments) -> Result<(), Vec<PublishError<M::ConstructMessageArguments>>> where M::ConstructMessageArguments: Clone
+	{
+		let mut publish_errors = Vec::new();
+		self.broadcast
+		(
+			construct_message_arguments,
+			|hyper_thread, construct_message_arguments|
+			{
+				if let Err(publish_error) = self.publisher.try_publish(hyper_thread, construct_message_arguments)
+				{
+					publish_errors.push(publish_error)
+				}
+			}
+		);
+		
+		if publish_errors.is_empty()
+		{
+			Ok(())
+		}
+		else
+		{
+			Err(publish_errors)
+		}
+	}
+	
+	#[inline(always)]
+	fn broadcast<Clonable: Clone>(&self, clonable: Clonable, mut publish: impl FnMut(HyperThread, Clonable))
+	{
+		let last_index = self.hyper_threads_to_publish_to.len() - 1;
+		for index in 0 .. last_index
+		{
+			publish(self.hyper_threads_to_publish_to.get_unchecked_value_safe(index), clonable.clone())
+		}
+		publish(self.hyper_threads_to_publish_to.get_unchecked_value_safe(last_index), clonable)
+	}
+}
@@ -0,0 +1,295 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A magic ring buffer of messages which are just a `TaggedNonNullPointer`, with no `MessageHeader`, and its own overflow list for messages spilled to the heap.
+///
+/// The pointer is that of a `Box<T>` and the tag bits are an opcode; `T` is the one type registered with the queue's `MessageHandlers` using `register_compact_message_handler()`, whose compressed type identifier is `compressed_type_identifier`.
+/// Each message is a single 8 byte word, half of an ordinary message of a `CompactMessage<T>` without its opcode (which has an 8 byte header followed by an 8 byte body).
+///
+/// As for a `PriorityLane`, messages are first-in, first-out and the queue's `OverflowPolicy` applies when the magic ring buffer is full.
+#[derive(Debug)]
+struct CompactLane
+{
+	compressed_type_identifier: CompressedTypeIdentifier,
+	magic_ring_buffer: MagicRingBuffer,
+	queue_occupancy: QueueOccupancy,
+	overflow: Mutex<VecDeque<TaggedNonNullPointer>>,
+	overflow_is_not_empty: AtomicBool,
+	number_of_oldest_messages_to_evict: AtomicU64,
+}
+
+impl CompactLane
+{
+	const WordSize: usize = size_of::<TaggedNonNullPointer>();
+	
+	#[inline(always)]
+	fn new(compressed_type_identifier: CompressedTypeIdentifier, defaults: &DefaultHugePageSizes, maximum_number_of_messages: NonZeroU64, inclusive_maximum_bytes_wasted: u64) -> Result<Self, MirroredMemoryMapCreationError>
+	{
+		let size_in_bytes = new_non_zero_u64(maximum_number_of_messages.get() * (Self::WordSize as u64));
+		Ok
+		(
+			Self
+			{
+				compressed_type_identifier,
+				magic_ring_buffer: MagicRingBuffer::allocate(defaults, size_in_bytes, inclusive_maximum_bytes_wasted)?,
+				queue_occupancy: QueueOccupancy::new(size_in_bytes),
+				overflow: Mutex::new(VecDeque::new()),
+				overflow_is_not_empty: AtomicBool::new(false),
+				number_of_oldest_messages_to_evict: AtomicU64::new(0),
+			}
+		)
+	}
+	
+	/// Is `compressed_type_identifier` that of the `CompactMessage<T>` enqueued in this compact lane?
+	#[inline(always)]
+	fn is_for(&self, compressed_type_identifier: Option<CompressedTypeIdentifier>) -> bool
+	{
+		compressed_type_identifier == Some(self.compressed_type_identifier)
+	}
+	
+	/// Returns the number of bytes previously reserved if successful (`Some(0)` means the compact lane has transitioned from empty to non-empty), or `None` if full.
+	///
+	/// Once reserved, a message must be written with `write()`.
+	#[inline(always)]
+	fn try_reserve(&self) -> Option<u64>
+	{
+		self.queue_occupancy.try_reserve(Self::WordSize)
+	}
+	
+	#[inline(always)]
+	fn write(&self, tagged_non_null_pointer: TaggedNonNullPointer)
+	{
+		self.magic_ring_buffer.write_some_data(Self::WordSize, |buffer| unsafe { write(buffer.as_mut_ptr() as *mut TaggedNonNullPointer, tagged_non_null_pointer) });
+	}
+	
+	/// Processes the next message, if any, in the magic ring buffer or, if that is empty (and no space in it is still reserved), the overflow list, as if it were an ordinary message of `CompactMessage<T>` without any message header extensions.
+	///
+	/// Returns the number of bytes dequeued if there was a message.
+	#[inline(always)]
+	fn process_next_message<DequeuedMessageProcessingError>(&self, message_processor: &mut impl FnMut(CompressedTypeIdentifier, MessageHeaderExtensions, NonNull<VariablySizedMessageBody>) -> Result<(), DequeuedMessageProcessingError>) -> Result<Option<usize>, DequeuedMessageProcessingError>
+	{
+		let compressed_type_identifier = self.compressed_type_identifier;
+		let mut untagged_message_processor = |tagged_non_null_pointer| message_processor(compressed_type_identifier, MessageHeaderExtensions::default(), UntaggedCompactMessage::untag(tagged_non_null_pointer).as_variably_sized_message_body());
+		
+		let (_more_data_to_read, bytes_dequeued) = self.process_next_message_in_magic_ring_buffer(&mut untagged_message_processor)?;
+		if bytes_dequeued != 0
+		{
+			return Ok(Some(bytes_dequeued))
+		}
+		
+		// Space is still reserved for a message which is being written; it was published before any message spilled since.
+		if self.queue_occupancy.is_not_empty()
+		{
+			return Ok(None)
+		}
+		
+		match self.pop_spilled_message()
+		{
+			None => Ok(None),
+			
+			Some(tagged_non_null_pointer) =>
+			{
+				untagged_message_processor(tagged_non_null_pointer)?;
+				Ok(Some(Self::WordSize))
+			}
+		}
+	}
+	
+	/// Processes the next message, if any, in the magic ring buffer, and releases the bytes it occupied.
+	///
+	/// Returns `(more_data_to_read, bytes_dequeued)`.
+	#[inline(always)]
+	fn process_next_message_in_magic_ring_buffer<E>(&self, mut message_processor: impl FnMut(TaggedNonNullPointer) -> Result<(), E>) -> Result<(bool, usize), E>
+	{
+		let mut bytes_dequeued = 0;
+		let more_data_to_read = self.magic_ring_buffer.single_reader_read_some_data::<E, _>
+		(
+			|buffer|
+			{
+				debug_assert!(buffer.len() >= Self::WordSize, "Buffer is too small to contain a TaggedNonNullPointer");
+				let tagged_non_null_pointer = unsafe { read(buffer.as_ptr() as *const TaggedNonNullPointer) };
+				bytes_dequeued = Self::WordSize;
+				(Self::WordSize, message_processor(tagged_non_null_pointer))
+			}
+		);
+		self.queue_occupancy.release(bytes_dequeued);
+		Ok((more_data_to_read?, bytes_dequeued))
+	}
+	
+	/// Drops every message in the magic ring buffer and the overflow list in place.
+	#[inline(always)]
+	fn drop_all_messages_in_place<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>(&self, message_handlers: &MessageHandlers<MessageHandlerArguments, Result<(), DequeuedMessageProcessingError>>)
+	{
+		while
+		{
+			let (more_data_to_read, _bytes_dequeued) = self.process_next_message_in_magic_ring_buffer::<()>
+			(
+				|tagged_non_null_pointer|
+				{
+					self.drop_in_place(message_handlers, tagged_non_null_pointer);
+					Ok(())
+				}
+			).expect("Should never happen");
+			
+			more_data_to_read
+		}
+		{
+		}
+		
+		for tagged_non_null_pointer in self.overflow.lock().unwrap().drain(..)
+		{
+			self.drop_in_place(message_handlers, tagged_non_null_pointer)
+		}
+	}
+	
+	/// Drops the oldest messages in the magic ring buffer, then, if that is not enough, in the overflow list, to make up for those spilled by the `DropOldest` overflow policy.
+	///
+	/// Eviction is deferred until dequeue because only the single consumer may read from the magic ring buffer.
+	#[inline(always)]
+	fn evict_oldest_messages<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>(&self, message_handlers: &MessageHandlers<MessageHandlerArguments, Result<(), DequeuedMessageProcessingError>>, overflow_counters: &OverflowCounters)
+	{
+		let number_of_oldest_messages_to_evict = self.number_of_oldest_messages_to_evict.swap(0, AcqRel);
+		
+		let mut number_of_messages_evicted = 0;
+		while number_of_messages_evicted < number_of_oldest_messages_to_evict
+		{
+			let (more_data_to_read, bytes_dequeued) = self.process_next_message_in_magic_ring_buffer::<()>
+			(
+				|tagged_non_null_pointer|
+				{
+					self.drop_in_place(message_handlers, tagged_non_null_pointer);
+					overflow_counters.increment_dropped_oldest();
+					Ok(())
+				}
+			).expect("Should never happen");
+			
+			if bytes_dequeued == 0
+			{
+				break
+			}
+			number_of_messages_evicted += 1;
+			
+			if !more_data_to_read
+			{
+				break
+			}
+		}
+		
+		while number_of_messages_evicted < number_of_oldest_messages_to_evict
+		{
+			match self.pop_spilled_message()
+			{
+				None => break,
+				
+				Some(tagged_non_null_pointer) =>
+				{
+					self.drop_in_place(message_handlers, tagged_non_null_pointer);
+					overflow_counters.increment_dropped_oldest();
+					number_of_messages_evicted += 1
+				}
+			}
+		}
+	}
+	
+	/// Records that one of the oldest messages should be evicted at the next dequeue.
+	#[inline(always)]
+	fn evict_oldest_message_later(&self)
+	{
+		self.number_of_oldest_messages_to_evict.fetch_add(1, AcqRel);
+	}
+	
+	#[inline(always)]
+	fn must_spill_to_preserve_ordering(&self, overflow_policy: OverflowPolicy) -> bool
+	{
+		overflow_policy.spills() && self.overflow_is_not_empty.load(Acquire)
+	}
+	
+	/// Appends a message to `overflow`.
+	#[inline(always)]
+	fn push_spilled_message(&self, tagged_non_null_pointer: TaggedNonNullPointer)
+	{
+		let mut overflow = self.overflow.lock().unwrap();
+		overflow.push_back(tagged_non_null_pointer);
+		self.overflow_is_not_empty.store(true, SeqCst);
+	}
+	
+	/// Appends a message to `overflow`, then, whilst `overflow` holds more messages than the magic ring buffer can, drops its oldest messages in place.
+	///
+	/// Used by the `DropOldest` overflow policy; as those dropped messages were to be evicted at the next dequeue anyway, they are no longer.
+	#[inline(always)]
+	fn push_spilled_message_dropping_oldest<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>(&self, tagged_non_null_pointer: TaggedNonNullPointer, message_handlers: &MessageHandlers<MessageHandlerArguments, Result<(), DequeuedMessageProcessingError>>, overflow_counters: &OverflowCounters)
+	{
+		let maximum_number_of_messages = (self.capacity_in_bytes() / (Self::WordSize as u64)) as usize;
+		let mut oldest_spilled_messages = Vec::new();
+		
+		{
+			let mut overflow = self.overflow.lock().unwrap();
+			overflow.push_back(tagged_non_null_pointer);
+			while overflow.len() > maximum_number_of_messages
+			{
+				oldest_spilled_messages.push(overflow.pop_front().unwrap())
+			}
+			self.overflow_is_not_empty.store(true, SeqCst);
+		}
+		
+		// Dropped once the lock is released, as a message's drop may itself publish.
+		for oldest_spilled_message in oldest_spilled_messages
+		{
+			let _ = self.number_of_oldest_messages_to_evict.fetch_update(AcqRel, Acquire, |number_of_oldest_messages_to_evict| Some(number_of_oldest_messages_to_evict.saturating_sub(1)));
+			self.drop_in_place(message_handlers, oldest_spilled_message);
+			overflow_counters.increment_dropped_oldest()
+		}
+	}
+	
+	#[inline(always)]
+	fn pop_spilled_message(&self) -> Option<TaggedNonNullPointer>
+	{
+		if !self.overflow_is_not_empty.load(Acquire)
+		{
+			return None
+		}
+		
+		let mut overflow = self.overflow.lock().unwrap();
+		let tagged_non_null_pointer = overflow.pop_front();
+		if overflow.is_empty()
+		{
+			self.overflow_is_not_empty.store(false, SeqCst)
+		}
+		tagged_non_null_pointer
+	}
+	
+	#[inline(always)]
+	fn drop_in_place<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>(&self, message_handlers: &MessageHandlers<MessageHandlerArguments, Result<(), DequeuedMessageProcessingError>>, tagged_non_null_pointer: TaggedNonNullPointer)
+	{
+		message_handlers.drop_in_place(self.compressed_type_identifier, UntaggedCompactMessage::untag(tagged_non_null_pointer).as_variably_sized_message_body())
+	}
+	
+	/// The capacity of the magic ring buffer.
+	#[inline(always)]
+	fn capacity_in_bytes(&self) -> u64
+	{
+		self.queue_occupancy.capacity_in_bytes()
+	}
+	
+	/// The number of bytes in the magic ring buffer, or `u64::MAX` if messages have been spilled to the heap.
+	#[inline(always)]
+	fn load(&self) -> u64
+	{
+		if self.overflow_is_not_empty.load(Relaxed)
+		{
+			u64::MAX
+		}
+		else
+		{
+			self.queue_occupancy.bytes_reserved()
+		}
+	}
+	
+	/// Are there messages in either the magic ring buffer or the overflow list?
+	#[inline(always)]
+	fn has_pending_messages(&self) -> bool
+	{
+		self.queue_occupancy.is_not_empty() || self.overflow_is_not_empty.load(SeqCst)
+	}
+}
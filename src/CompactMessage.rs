@@ -0,0 +1,44 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// The message body registered for a `Box<T>` published with an opcode by a `CompactPublisher`.
+///
+/// In a compact lane, only a `TaggedNonNullPointer` is enqueued, whose tag bits are `opcode`; when dequeued, it is untagged into an `UntaggedCompactMessage`, which has the same representation as this.
+/// In a queue without a compact lane, this is enqueued as an ordinary message.
+#[repr(C)]
+#[derive(Debug)]
+struct CompactMessage<T: 'static>
+{
+	boxed: Option<Box<T>>,
+	opcode: u32,
+}
+
+impl<T: 'static> CompactMessage<T>
+{
+	#[inline(always)]
+	fn new(boxed: Box<T>, opcode: u32) -> Self
+	{
+		Self
+		{
+			boxed: Some(boxed),
+			opcode,
+		}
+	}
+	
+	/// Hands off `boxed` to `compact_message_handler`; the now empty message is dropped in place afterwards as usual.
+	#[inline(always)]
+	fn handle_message<MessageHandlerArguments, DequeuedMessageProcessingError>(&mut self, compact_message_handler: &impl Fn(Box<T>, u32, &MessageHandlerArguments) -> Result<(), DequeuedMessageProcessingError>, message_handler_arguments: &MessageHandlerArguments) -> Result<(), DequeuedMessageProcessingError>
+	{
+		let boxed = self.boxed.take().expect("A compact message is only ever handled once");
+		compact_message_handler(boxed, self.opcode, message_handler_arguments)
+	}
+	
+	/// `T` must be aligned to at least `TaggedNonNullPointer::MinimumPointerAlignment`, as the lower bits of the pointer are discarded.
+	#[inline(always)]
+	fn tag(boxed: Box<T>, opcode: u32) -> TaggedNonNullPointer
+	{
+		debug_assert!(align_of::<T>() >= TaggedNonNullPointer::MinimumPointerAlignment, "T is not sufficiently aligned");
+		TaggedNonNullPointer::tag(opcode, new_non_null(Box::into_raw(boxed) as *mut ()))
+	}
+}
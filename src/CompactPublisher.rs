@@ -0,0 +1,95 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A publisher for one type of boxed value, `Box<T>`, which hands off ownership of the box with a 19-bit opcode using a single 8 byte word in a queue's compact lane.
+///
+/// The word is a `TaggedNonNullPointer` whose tag bits are the opcode; `T` must have been registered using `MessageHandlers::register_compact_message_handler()`, which hands the box and opcode to its message handler.
+///
+/// If a queue has no compact lane (see `QueueSettings.compact_lane_maximum_number_of_messages`), or when publishing with a priority other than `Priority::Highest`, the box and opcode are published as an ordinary message in a priority lane.
+/// Otherwise, if the compact lane is full, the queue's `OverflowPolicy` applies as it would for a priority lane.
+///
+/// `T` must be aligned to at least 8 bytes, as the lower bits of the pointer are discarded.
+///
+/// See `Publisher` for why this holds a `*const Queue`, and for what happens when publishing to a queue whose `MessageHandlers` do not have `T` registered.
+#[derive(Debug)]
+pub struct CompactPublisher<T: 'static, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>
+{
+	_queues_drop_reference: Queues<MessageHandlerArguments, DequeuedMessageProcessingError>,
+	queues_mapped: PerBitSetAwareData<HyperThread, (*const Queue<MessageHandlerArguments, DequeuedMessageProcessingError>, Option<CompressedTypeIdentifier>)>,
+	default_hyper_thread: HyperThread,
+	marker: PhantomData<Box<T>>,
+}
+
+impl<T: 'static, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> CompactPublisher<T, MessageHandlerArguments, DequeuedMessageProcessingError>
+{
+	/// The largest opcode, `2^19 - 1`, which is the largest value of the tag bits of a `TaggedNonNullPointer` that is platform-portable.
+	pub const MaximumOpcode: u32 = (1 << 19) - 1;
+	
+	#[inline(always)]
+	fn new(queues: &Queues<MessageHandlerArguments, DequeuedMessageProcessingError>, default_hyper_thread: HyperThread) -> Self
+	{
+		assert!(align_of::<T>() >= TaggedNonNullPointer::MinimumPointerAlignment, "T `{}` must be aligned to at least `{}` bytes", type_name::<T>(), TaggedNonNullPointer::MinimumPointerAlignment);
+		
+		Self
+		{
+			_queues_drop_reference: queues.clone(),
+			queues_mapped: queues.0.map_ref(|_hyper_thread, queue| (queue as *const _, queue.fixed_sized_message_body_compressed_type_identifier::<CompactMessage<T>>())),
+			default_hyper_thread,
+			marker: PhantomData,
+		}
+	}
+	
+	/// A publisher publishes to a specific hyper thread, handing off `boxed` with `opcode` to it.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	///
+	/// Panics if `opcode` exceeds `MaximumOpcode`.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish(&self, hyper_thread: HyperThread, opcode: u32, boxed: Box<T>) -> HyperThread
+	{
+		self.publish_with_priority(hyper_thread, Priority::Highest, opcode, boxed)
+	}
+	
+	/// As for `publish()`, but publishes to the priority lane for `priority`, unless `priority` is `Priority::Highest` and the queue has a compact lane.
+	///
+	/// If the queue has fewer priority lanes than `priority` needs, publishes to its lowest priority lane.
+	#[inline(always)]
+	pub fn publish_with_priority(&self, hyper_thread: HyperThread, priority: Priority, opcode: u32, boxed: Box<T>) -> HyperThread
+	{
+		assert!(opcode <= Self::MaximumOpcode, "opcode `{}` exceeds MaximumOpcode", opcode);
+		
+		let (&(queue, compressed_type_identifier), actual_hyper_thread) = self.queues_mapped.get_or(hyper_thread, self.default_hyper_thread);
+		
+		unsafe { (& * queue).enqueue_compact(compressed_type_identifier, priority, boxed, opcode) };
+		actual_hyper_thread
+	}
+	
+	/// A publisher publishes to a specific hyper thread, handing off `boxed` with `opcode` to it, without waiting for space in the queue.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	///
+	/// Panics if `opcode` exceeds `MaximumOpcode`.
+	///
+	/// Returns the actual hyper thread published to, or, if its queue is full, a `PublishError::QueueFull` which hands back `boxed`.
+	#[inline(always)]
+	pub fn try_publish(&self, hyper_thread: HyperThread, opcode: u32, boxed: Box<T>) -> Result<HyperThread, PublishError<Box<T>>>
+	{
+		self.try_publish_with_priority(hyper_thread, Priority::Highest, opcode, boxed)
+	}
+	
+	/// As for `try_publish()`, but publishes to the priority lane for `priority`, unless `priority` is `Priority::Highest` and the queue has a compact lane.
+	///
+	/// If the queue has fewer priority lanes than `priority` needs, publishes to its lowest priority lane.
+	#[inline(always)]
+	pub fn try_publish_with_priority(&self, hyper_thread: HyperThread, priority: Priority, opcode: u32, boxed: Box<T>) -> Result<HyperThread, PublishError<Box<T>>>
+	{
+		assert!(opcode <= Self::MaximumOpcode, "opcode `{}` exceeds MaximumOpcode", opcode);
+		
+		let (&(queue, compressed_type_identifier), actual_hyper_thread) = self.queues_mapped.get_or(hyper_thread, self.default_hyper_thread);
+		
+		unsafe { (& * queue).try_enqueue_compact(actual_hyper_thread, compressed_type_identifier, priority, boxed, opcode) }
+	}
+}
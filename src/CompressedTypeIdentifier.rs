@@ -3,13 +3,35 @@
 
 
 /// A compressed type identifier is more efficient to use than a `TypeId`, but only be used for up to 256 types.
+///
+/// If the `wide-compressed-type-identifier` feature is enabled, it can be used for up to 65,536 types.
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
+#[cfg(not(feature = "wide-compressed-type-identifier"))]
 pub struct CompressedTypeIdentifier(u8);
 
+/// A compressed type identifier is more efficient to use than a `TypeId`, but only be used for up to 65,536 types.
+///
+/// If the `wide-compressed-type-identifier` feature is not enabled, it can only be used for up to 256 types.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+#[cfg(feature = "wide-compressed-type-identifier")]
+pub struct CompressedTypeIdentifier(u16);
+
 impl CompressedTypeIdentifier
 {
+	#[cfg(not(feature = "wide-compressed-type-identifier"))]
 	const ExclusiveMaximum: usize = (u8::MAX as usize) + 1;
+	
+	#[cfg(feature = "wide-compressed-type-identifier")]
+	const ExclusiveMaximum: usize = (u16::MAX as usize) + 1;
+	
+	/// Few applications register anywhere near 65,536 types, so memory for all of them is not reserved up front.
+	#[cfg(feature = "wide-compressed-type-identifier")]
+	const InitialCapacity: usize = 256;
+	
+	#[cfg(not(feature = "wide-compressed-type-identifier"))]
+	const InitialCapacity: usize = Self::ExclusiveMaximum;
 
 	#[inline(always)]
 	fn index(self) -> usize
@@ -18,10 +40,17 @@ impl CompressedTypeIdentifier
 	}
 	
 	#[inline(always)]
-	fn next<A: Array>(array: &ArrayVec<A>) -> Self
+	fn from_index(index: usize) -> Self
+	{
+		debug_assert!(index < Self::ExclusiveMaximum, "index is too large");
+		Self(index as _)
+	}
+	
+	/// `number_of_registered_types` is the number of types registered so far.
+	#[inline(always)]
+	fn next(number_of_registered_types: usize) -> Self
 	{
-		let length = array.len();
-		debug_assert_ne!(length, A::CAPACITY, "No more space available");
-		Self(length as u8)
+		assert!(number_of_registered_types < Self::ExclusiveMaximum, "No more space available");
+		Self(number_of_registered_types as _)
 	}
 }
@@ -0,0 +1,65 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A token enqueued by a `ConflatingPublisher`.
+///
+/// The message itself is held in `pending_messages` until the token is handled, so that a later message for the same key can replace it in place.
+#[derive(Debug)]
+struct ConflatedMessage<M: 'static + Message, Key: 'static + Hash + Eq>
+{
+	pending_messages: Arc<Mutex<HashMap<Key, M>>>,
+	key: Key,
+	is_pending: bool,
+}
+
+impl<M: 'static + Message, Key: 'static + Hash + Eq> Drop for ConflatedMessage<M, Key>
+{
+	/// A token can be dropped without being handled, eg when evicted or when its queue is dropped; the pending message must not be stranded, otherwise all later messages for the key would be silently discarded.
+	#[inline(always)]
+	fn drop(&mut self)
+	{
+		if self.is_pending
+		{
+			if let Ok(mut pending_messages) = self.pending_messages.lock()
+			{
+				pending_messages.remove(&self.key);
+			}
+		}
+	}
+}
+
+impl<M: 'static + Message, Key: 'static + Hash + Eq> ConflatedMessage<M, Key>
+{
+	#[inline(always)]
+	fn new(pending_messages: &Arc<Mutex<HashMap<Key, M>>>, key: Key) -> Self
+	{
+		Self
+		{
+			pending_messages: pending_messages.clone(),
+			key,
+			is_pending: true,
+		}
+	}
+	
+	/// A token which was never enqueued has no pending message to remove when dropped; nor must it take the lock on `pending_messages`, which may already be held.
+	#[inline(always)]
+	fn discard_without_enqueuing(mut self)
+	{
+		self.is_pending = false
+	}
+	
+	#[inline(always)]
+	fn handle_message(&mut self, message_handler_arguments: &M::MessageHandlerArguments) -> Result<(), M::DequeuedMessageProcessingError>
+	{
+		// Once removed, a message for the same key may be published (with a new token) before this token is dropped.
+		self.is_pending = false;
+		let latest_message = self.pending_messages.lock().unwrap().remove(&self.key);
+		match latest_message
+		{
+			None => Ok(()),
+			
+			Some(mut latest_message) => latest_message.handle_message(message_handler_arguments),
+		}
+	}
+}
@@ -0,0 +1,150 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A publisher for one type of message, `M`, which conflates messages by `Key`.
+///
+/// Publishing a message whose key already has an undelivered message replaces that message in place, dropping the older one, rather than enqueuing another; only the latest message for a key is ever handled.
+/// This bounds the growth of a queue under bursts of, say, price updates or state snapshots to one message per key.
+///
+/// Messages are moved, not constructed in place; `Message::construct_message()` is never called.
+///
+/// Undelivered messages are held by the publisher, not the queue, so only messages published with the same `ConflatingPublisher` are conflated; messages for the same key published with another `ConflatingPublisher`, even to the same queue, are delivered separately.
+///
+/// `M` must have been registered using `MessageHandlers::register_conflated_message_handler()`.
+#[derive(Debug)]
+pub struct ConflatingPublisher<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, Key: 'static + Hash + Eq + Clone, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>
+{
+	publisher: ValuePublisher<ConflatedMessage<M, Key>, MessageHandlerArguments, DequeuedMessageProcessingError>,
+	pending_messages: Arc<Mutex<HashMap<Key, M>>>,
+}
+
+impl<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, Key: 'static + Hash + Eq + Clone, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> ConflatingPublisher<M, Key, MessageHandlerArguments, DequeuedMessageProcessingError>
+{
+	#[inline(always)]
+	fn new(queues: &Queues<MessageHandlerArguments, DequeuedMessageProcessingError>, default_hyper_thread: HyperThread) -> Self
+	{
+		Self
+		{
+			publisher: ValuePublisher::new(queues, default_hyper_thread),
+			pending_messages: Arc::new(Mutex::new(HashMap::default())),
+		}
+	}
+	
+	/// A publisher publishes to a specific hyper thread, moving `message` into the queue.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	///
+	/// Returns the actual hyper thread published to, or `None` if `message` replaced an undelivered message for `key`; `message` is then delivered to the hyper thread the undelivered message was published to.
+	///
+	/// If the queue is full, its `OverflowPolicy` applies; with `OverflowPolicy::DropNewest`, `message` is dropped (rather than replacing an undelivered message for `key`) and, as for `Publisher::publish()`, the actual hyper thread is still returned.
+	///
+	/// Panics if `M` is not registered with the queue.
+	#[inline(always)]
+	pub fn publish(&self, hyper_thread: HyperThread, key: Key, message: M) -> Option<HyperThread>
+	{
+		use self::PublishError::*;
+		
+		let message = match self.try_publish(hyper_thread, key.clone(), message)
+		{
+			Ok(published) => return published,
+			
+			Err(QueueFull { hyper_thread: actual_hyper_thread, construct_message_arguments: message, .. }) =>
+			{
+				let queue = self.publisher.queue(hyper_thread);
+				if queue.overflow_policy == OverflowPolicy::DropNewest
+				{
+					queue.overflow_counters().increment_dropped_newest();
+					return Some(actual_hyper_thread)
+				}
+				message
+			}
+			
+			Err(TooLarge { bytes_needed, .. }) => panic!("Message of `{}` bytes is larger than the queue", bytes_needed),
+			
+			Err(Unregistered { .. }) => panic!("Unregistered ConflatedMessage `{}`; use MessageHandlers::register_conflated_message_handler()", type_name::<M>()),
+			
+			Err(DeadLettered { .. }) => unreachable!("try_publish() never dead letters"),
+		};
+		
+		// Every other overflow policy always enqueues the token, so `message` can be made pending first; the lock can not be held whilst waiting for space, which only the subscriber (which takes the lock to handle a token) can make, nor whilst evicting the oldest messages (whose tokens take the lock when dropped).
+		if self.replace_or_insert_pending_message(&key, message)
+		{
+			return None
+		}
+		
+		Some(self.publisher.publish(hyper_thread, ConflatedMessage::new(&self.pending_messages, key)))
+	}
+	
+	/// A publisher publishes to a specific hyper thread, moving `message` into the queue, without waiting for space in the queue.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	///
+	/// Returns the actual hyper thread published to, or `None` if `message` replaced an undelivered message for `key` (which never needs space in the queue), or a `PublishError` which hands back `message`.
+	///
+	/// If `M` is not registered with the queue, `message` is handed back in a `PublishError::Unregistered` and never moved to the dead letter sink.
+	#[inline(always)]
+	pub fn try_publish(&self, hyper_thread: HyperThread, key: Key, message: M) -> Result<Option<HyperThread>, PublishError<M>>
+	{
+		use self::PublishError::*;
+		
+		// Held until the token is enqueued and `message` inserted, or the token handed back, so that the token can not be handled before `message` is pending and no other publish can replace a `message` for which no token was enqueued.
+		let mut pending_messages = self.pending_messages.lock().unwrap();
+		
+		if let Some(undelivered_message) = pending_messages.get_mut(&key)
+		{
+			*undelivered_message = message;
+			return Ok(None)
+		}
+		
+		// A token moved to the dead letter sink might be dropped, and so take the lock, whilst it is still held.
+		if let Err(actual_hyper_thread) = self.publisher.actual_hyper_thread_if_registered(hyper_thread)
+		{
+			return Err(Unregistered { hyper_thread: actual_hyper_thread, construct_message_arguments: message })
+		}
+		
+		match self.publisher.try_publish(hyper_thread, ConflatedMessage::new(&self.pending_messages, key.clone()))
+		{
+			Ok(actual_hyper_thread) =>
+			{
+				pending_messages.insert(key, message);
+				Ok(Some(actual_hyper_thread))
+			}
+			
+			Err(QueueFull { hyper_thread, bytes_needed, construct_message_arguments: conflated_message }) =>
+			{
+				conflated_message.discard_without_enqueuing();
+				Err(QueueFull { hyper_thread, bytes_needed, construct_message_arguments: message })
+			}
+			
+			Err(TooLarge { hyper_thread, bytes_needed, construct_message_arguments: conflated_message }) =>
+			{
+				conflated_message.discard_without_enqueuing();
+				Err(TooLarge { hyper_thread, bytes_needed, construct_message_arguments: message })
+			}
+			
+			Err(Unregistered { .. }) | Err(DeadLettered { .. }) => unreachable!("M is registered"),
+		}
+	}
+	
+	/// Returns `true` if an undelivered message for `key` was replaced.
+	#[inline(always)]
+	fn replace_or_insert_pending_message(&self, key: &Key, message: M) -> bool
+	{
+		let mut pending_messages = self.pending_messages.lock().unwrap();
+		match pending_messages.get_mut(key)
+		{
+			Some(undelivered_message) =>
+			{
+				*undelivered_message = message;
+				true
+			}
+			
+			None =>
+			{
+				pending_messages.insert(key.clone(), message);
+				false
+			}
+		}
+	}
+}
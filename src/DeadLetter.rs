@@ -0,0 +1,159 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A message moved out of a queue (or never allowed into it) because it could not be handled.
+///
+/// Owns the message body, which lives on the heap; dropping a dead letter drops the message body in place.
+/// Use `downcast()` to recover the message.
+#[derive(Debug)]
+pub struct DeadLetter
+{
+	compressed_type_identifier: Option<CompressedTypeIdentifier>,
+	type_identifier: TypeId,
+	type_name: &'static str,
+	body: NonNull<u8>,
+	layout: Layout,
+	drop_in_place_function_pointer: DropVariablySizedMessageBodyInPlaceFunctionPointer,
+}
+
+unsafe impl Send for DeadLetter
+{
+}
+
+impl Drop for DeadLetter
+{
+	#[inline(always)]
+	fn drop(&mut self)
+	{
+		(self.drop_in_place_function_pointer)(self.variably_sized_message_body());
+		self.deallocate()
+	}
+}
+
+impl DeadLetter
+{
+	/// The compressed type identifier of the message, if it was registered.
+	#[inline(always)]
+	pub fn compressed_type_identifier(&self) -> Option<CompressedTypeIdentifier>
+	{
+		self.compressed_type_identifier
+	}
+	
+	/// The type identifier of the message.
+	#[inline(always)]
+	pub fn type_identifier(&self) -> TypeId
+	{
+		self.type_identifier
+	}
+	
+	/// The type name of the message (as returned by `std::any::type_name()`).
+	#[inline(always)]
+	pub fn type_name(&self) -> &'static str
+	{
+		self.type_name
+	}
+	
+	/// Moves the message out if it is of type `FixedSizeMessageBody`.
+	#[inline(always)]
+	pub fn downcast<FixedSizeMessageBody: 'static>(self) -> Result<Box<FixedSizeMessageBody>, Self>
+	{
+		if self.type_identifier == TypeId::of::<FixedSizeMessageBody>()
+		{
+			debug_assert_eq!(self.layout, Layout::new::<FixedSizeMessageBody>());
+			let body = self.body.as_ptr() as *mut FixedSizeMessageBody;
+			forget(self);
+			Ok(unsafe { Box::from_raw(body) })
+		}
+		else
+		{
+			Err(self)
+		}
+	}
+	
+	/// Moves the header and payload out if the message is a `VariablySizedMessage` of type `Header`.
+	#[inline(always)]
+	pub fn downcast_with_payload<Header: 'static + VariablySizedMessage>(self) -> Result<(Box<Header>, Box<[u8]>), Self>
+	{
+		if self.type_identifier == TypeId::of::<HeaderAndPayload<Header>>()
+		{
+			let (header, payload) = unsafe { HeaderAndPayload::<Header>::into_header_and_payload(self.body.cast()) };
+			self.deallocate();
+			forget(self);
+			Ok((Box::new(header), payload))
+		}
+		else
+		{
+			Err(self)
+		}
+	}
+	
+	/// Moves a message out of a queue by copying its bytes; the message in the queue must then be treated as moved from and not dropped.
+	#[inline(always)]
+	pub(crate) fn move_from<MessageHandlerArguments, MessageHandlerReturns>(compressed_type_identifier: CompressedTypeIdentifier, message_type_registration: &MessageTypeRegistration<MessageHandlerArguments, MessageHandlerReturns>, variably_sized_message_body: NonNull<VariablySizedMessageBody>) -> Self
+	{
+		let layout = message_type_registration.layout(variably_sized_message_body);
+		let body = Self::allocate(layout);
+		unsafe { copy_nonoverlapping(variably_sized_message_body.as_ptr() as *const u8, body.as_ptr(), layout.size()) };
+		
+		Self
+		{
+			compressed_type_identifier: Some(compressed_type_identifier),
+			type_identifier: message_type_registration.type_identifier,
+			type_name: message_type_registration.type_name,
+			body,
+			layout,
+			drop_in_place_function_pointer: message_type_registration.drop_in_place_function_pointer,
+		}
+	}
+	
+	/// Constructs a message, followed by `trailing_bytes` (eg the payload of a `VariablySizedMessage`), directly on the heap.
+	#[inline(always)]
+	pub(crate) fn construct<FixedSizeMessageBody: 'static + Sized>(trailing_bytes: usize, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>)) -> Self
+	{
+		let layout = Layout::from_size_align(size_of::<FixedSizeMessageBody>() + trailing_bytes, align_of::<FixedSizeMessageBody>()).unwrap();
+		let body = Self::allocate(layout);
+		fixed_size_message_body_constructor(body.cast());
+		
+		Self
+		{
+			compressed_type_identifier: None,
+			type_identifier: TypeId::of::<FixedSizeMessageBody>(),
+			type_name: type_name::<FixedSizeMessageBody>(),
+			body,
+			layout,
+			drop_in_place_function_pointer: unsafe { transmute(VirtualMethodTablePointer::from_any::<FixedSizeMessageBody>().drop_in_place_function_pointer()) },
+		}
+	}
+	
+	#[inline(always)]
+	fn variably_sized_message_body(&self) -> NonNull<VariablySizedMessageBody>
+	{
+		self.body.cast()
+	}
+	
+	#[inline(always)]
+	fn allocate(layout: Layout) -> NonNull<u8>
+	{
+		if layout.size() == 0
+		{
+			return new_non_null(layout.align() as *mut u8)
+		}
+		
+		let body = unsafe { alloc(layout) };
+		if body.is_null()
+		{
+			handle_alloc_error(layout)
+		}
+		new_non_null(body)
+	}
+	
+	#[inline(always)]
+	fn deallocate(&self)
+	{
+		if self.layout.size() != 0
+		{
+			unsafe { dealloc(self.body.as_ptr(), self.layout) }
+		}
+	}
+}
@@ -0,0 +1,19 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// Why a message became a `DeadLetter`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DeadLetterReason
+{
+	/// The message handler returned an error (which is separately passed to the `DequeuedMessageProcessingErrorPolicy`).
+	MessageHandlerFailed,
+	
+	/// The message handler panicked (only if `QueueSettings.catch_message_handler_panics` is `true`).
+	MessageHandlerPanicked,
+	
+	/// The message's type was not registered with the `MessageHandlers` of the queue it was published to.
+	///
+	/// The message was constructed directly on the heap and never entered the queue.
+	Unregistered,
+}
@@ -0,0 +1,16 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// Receives messages which could not be handled, moved rather than dropped, so that they can be logged, retried or forwarded (eg to a supervisor hyper thread).
+///
+/// Set using `MessageHandlers::set_dead_letter_sink()`.
+///
+/// Called on the subscriber's thread for `DeadLetterReason::MessageHandlerFailed` and `DeadLetterReason::MessageHandlerPanicked`, and on the publisher's thread for `DeadLetterReason::Unregistered`.
+pub trait DeadLetterSink: Debug + Send + Sync
+{
+	/// Receive a dead letter.
+	///
+	/// Dropping `dead_letter` drops the message in place.
+	fn receive_dead_letter(&self, dead_letter_reason: DeadLetterReason, dead_letter: DeadLetter);
+}
@@ -7,6 +7,6 @@
 /// All implementations of `FixedSizeMessageBody` when specified in `Enqueue::enqueue()`. must share the same `DequeuedMessageProcessingError` when dequeued and processed.
 trait Dequeue<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>
 {
-	/// Dequeues messages.
-	fn dequeue(&self, terminate: &Arc<impl Terminate>, message_handler_arguments: &MessageHandlerArguments) -> Result<(), DequeuedMessageProcessingError>;
+	/// Dequeues messages until there are none left, `terminate` becomes true, `dequeue_budget` is exhausted or `dequeued_message_processing_error_policy` stops dequeuing.
+	fn dequeue(&self, terminate: &Arc<impl Terminate>, message_handler_arguments: &MessageHandlerArguments, dequeue_budget: &DequeueBudget, dequeued_message_processing_error_policy: &mut DequeuedMessageProcessingErrorPolicy<DequeuedMessageProcessingError>) -> Result<DequeueOutcome, DequeueFailed<DequeuedMessageProcessingError>>;
 }
@@ -0,0 +1,81 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// Limits how much work is done by one call to receive and handle messages, so that a flood of messages can not starve everything else on a hyper thread.
+///
+/// Limits are checked before each message is dequeued, so at least one message is always dequeued if there is one; the budget may be exceeded by the last message dequeued.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DequeueBudget
+{
+	/// Maximum number of messages to dequeue; `None` is unlimited.
+	pub maximum_messages: Option<NonZeroU64>,
+	
+	/// Maximum number of bytes of messages (including message headers and padding) to dequeue; `None` is unlimited.
+	pub maximum_bytes: Option<NonZeroU64>,
+	
+	/// Maximum number of time stamp counter (TSC) cycles to spend dequeuing; `None` is unlimited.
+	///
+	/// The time stamp counter is only read if this is set.
+	/// On architectures other than x86-64 and AArch64, these are nanoseconds of the raw monotonic clock instead.
+	pub maximum_elapsed_time_stamp_counter_cycles: Option<NonZeroU64>,
+}
+
+impl DequeueBudget
+{
+	/// An unlimited budget; messages are dequeued until the queue is empty or termination.
+	pub const Unlimited: Self = Self
+	{
+		maximum_messages: None,
+		maximum_bytes: None,
+		maximum_elapsed_time_stamp_counter_cycles: None,
+	};
+	
+	#[inline(always)]
+	fn started_at(&self) -> u64
+	{
+		if self.maximum_elapsed_time_stamp_counter_cycles.is_some()
+		{
+			read_time_stamp_counter()
+		}
+		else
+		{
+			0
+		}
+	}
+	
+	#[inline(always)]
+	fn is_exhausted(&self, started_at: u64, dequeue_outcome: &DequeueOutcome) -> bool
+	{
+		if dequeue_outcome.messages_dequeued == 0
+		{
+			return false
+		}
+		
+		if let Some(maximum_messages) = self.maximum_messages
+		{
+			if dequeue_outcome.messages_dequeued >= maximum_messages.get()
+			{
+				return true
+			}
+		}
+		
+		if let Some(maximum_bytes) = self.maximum_bytes
+		{
+			if dequeue_outcome.bytes_dequeued >= maximum_bytes.get()
+			{
+				return true
+			}
+		}
+		
+		if let Some(maximum_elapsed_time_stamp_counter_cycles) = self.maximum_elapsed_time_stamp_counter_cycles
+		{
+			if read_time_stamp_counter().wrapping_sub(started_at) >= maximum_elapsed_time_stamp_counter_cycles.get()
+			{
+				return true
+			}
+		}
+		
+		false
+	}
+}
@@ -0,0 +1,36 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A message handler returned a `DequeuedMessageProcessingError` which the `DequeuedMessageProcessingErrorPolicy` did not continue from.
+///
+/// The failed message has already been dropped in place (exactly once) and the read position of the queue has been advanced past it.
+#[derive(Debug)]
+pub struct DequeueFailed<DequeuedMessageProcessingError: error::Error>
+{
+	/// The error returned by the message handler.
+	pub dequeued_message_processing_error: DequeuedMessageProcessingError,
+	
+	/// What was dequeued before the message handler failed; `stopped_because` is `DequeueStoppedBecause::MessageHandlerFailed`.
+	///
+	/// The failed message is not included in `messages_dequeued`.
+	pub dequeue_outcome: DequeueOutcome,
+}
+
+impl<DequeuedMessageProcessingError: error::Error> fmt::Display for DequeueFailed<DequeuedMessageProcessingError>
+{
+	#[inline(always)]
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result
+	{
+		Debug::fmt(self, f)
+	}
+}
+
+impl<DequeuedMessageProcessingError: 'static + error::Error> error::Error for DequeueFailed<DequeuedMessageProcessingError>
+{
+	#[inline(always)]
+	fn source(&self) -> Option<&(dyn error::Error + 'static)>
+	{
+		Some(&self.dequeued_message_processing_error)
+	}
+}
@@ -0,0 +1,85 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// The outcome of receiving and handling messages.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DequeueOutcome
+{
+	/// Number of messages dequeued (and handled).
+	pub messages_dequeued: u64,
+	
+	/// Number of messages dequeued which were not yet due and so were moved to the timer wheel rather than handled.
+	///
+	/// These are included in `messages_dequeued`, and are included again (with no bytes) once they are due and are handled.
+	pub messages_scheduled: u64,
+	
+	/// Number of bytes of messages (including message headers and padding) dequeued.
+	pub bytes_dequeued: u64,
+	
+	/// Number of messages dequeued which had expired and so were dropped in place without calling their message handler.
+	///
+	/// These are included in `messages_dequeued`.
+	pub messages_expired: u64,
+	
+	/// Number of messages dequeued whose message handler failed but for which the `DequeuedMessageProcessingErrorPolicy` continued dequeuing.
+	///
+	/// These are included in `messages_dequeued`.
+	pub message_handlers_failed: u64,
+	
+	/// Number of message handlers (including expired message handlers) which panicked, if `QueueSettings.catch_message_handler_panics` was `true`.
+	///
+	/// The messages concerned are included in `messages_dequeued`.
+	/// The panics are kept by the queue, even if dequeuing later fails, until taken with `Subscriber::take_message_handler_panics()`.
+	pub message_handlers_panicked: u64,
+	
+	/// When the next message in the timer wheel is due, if there are any messages in the timer wheel once dequeuing stopped.
+	///
+	/// Neither the eventfd nor the waker of an `AsyncSubscriber` is signalled when a message in the timer wheel becomes due, so arm a timer (eg a timerfd) for this time and then receive messages again.
+	/// It may be a little earlier than any message is due, and is in the past if a message is already due.
+	pub next_scheduled_message_due_at: Option<MonotonicTime>,
+	
+	/// Why dequeuing stopped.
+	pub stopped_because: DequeueStoppedBecause,
+}
+
+impl DequeueOutcome
+{
+	#[inline(always)]
+	fn record_message_dequeued(&mut self, bytes_dequeued: usize)
+	{
+		self.messages_dequeued += 1;
+		self.bytes_dequeued += bytes_dequeued as u64;
+	}
+	
+	#[inline(always)]
+	fn record_message_scheduled(&mut self)
+	{
+		self.messages_scheduled += 1;
+	}
+	
+	#[inline(always)]
+	fn record_message_expired(&mut self)
+	{
+		self.messages_expired += 1;
+	}
+	
+	#[inline(always)]
+	fn record_message_handler_failed(&mut self)
+	{
+		self.message_handlers_failed += 1;
+	}
+	
+	#[inline(always)]
+	fn record_message_handler_panicked(&mut self)
+	{
+		self.message_handlers_panicked += 1;
+	}
+	
+	#[inline(always)]
+	fn stopped_because(mut self, stopped_because: DequeueStoppedBecause) -> Self
+	{
+		self.stopped_because = stopped_because;
+		self
+	}
+}
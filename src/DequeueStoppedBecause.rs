@@ -0,0 +1,31 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// Why dequeuing stopped.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DequeueStoppedBecause
+{
+	/// There were no more messages.
+	QueueExhausted,
+	
+	/// The `DequeueBudget` was exhausted; there may be more messages.
+	BudgetExhausted,
+	
+	/// `Terminate::should_continue()` was `false`; there may be more messages.
+	Terminated,
+	
+	/// A message handler failed and the `DequeuedMessageProcessingErrorPolicy` did not continue; there may be more messages.
+	///
+	/// Only ever found in `DequeueFailed.dequeue_outcome`.
+	MessageHandlerFailed,
+}
+
+impl Default for DequeueStoppedBecause
+{
+	#[inline(always)]
+	fn default() -> Self
+	{
+		DequeueStoppedBecause::QueueExhausted
+	}
+}
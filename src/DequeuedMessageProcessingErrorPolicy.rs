@@ -0,0 +1,80 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// What to do when a message handler returns a `DequeuedMessageProcessingError`.
+///
+/// In all cases, by the time the policy is applied the failed message has already been dropped in place (exactly once) and the read position of the queue has been advanced past it; a failed message is never handled or dropped again.
+/// The policies differ only in what happens to the messages remaining in the queue.
+pub enum DequeuedMessageProcessingErrorPolicy<'a, DequeuedMessageProcessingError: error::Error>
+{
+	/// Stop dequeuing and return the error as `Err(DequeueFailed)`; remaining messages are left in the queue for the next call to receive and handle messages.
+	///
+	/// This is the behaviour of the `?` operator.
+	StopAndLeaveRemaining,
+	
+	/// Continue dequeuing, appending each error with the `CompressedTypeIdentifier` of the failed message to `collected_errors`.
+	///
+	/// `collected_errors` is bounded: once it contains `maximum_errors` errors, a further error is treated as for `StopAndLeaveRemaining`.
+	/// `collected_errors` is not cleared; it can be reused between calls to avoid allocation.
+	ContinueAndCollectErrors
+	{
+		/// Errors collected so far.
+		collected_errors: &'a mut Vec<(CompressedTypeIdentifier, DequeuedMessageProcessingError)>,
+		
+		/// Maximum length of `collected_errors`.
+		maximum_errors: usize,
+	},
+	
+	/// Continue dequeuing, passing each error with the `CompressedTypeIdentifier` of the failed message to a callback.
+	Callback(&'a mut dyn FnMut(CompressedTypeIdentifier, DequeuedMessageProcessingError)),
+}
+
+impl<'a, DequeuedMessageProcessingError: error::Error> Debug for DequeuedMessageProcessingErrorPolicy<'a, DequeuedMessageProcessingError>
+{
+	#[inline(always)]
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result
+	{
+		use self::DequeuedMessageProcessingErrorPolicy::*;
+		
+		match self
+		{
+			&StopAndLeaveRemaining => write!(f, "StopAndLeaveRemaining"),
+			
+			&ContinueAndCollectErrors { ref collected_errors, maximum_errors } => write!(f, "ContinueAndCollectErrors {{ collected_errors: {:?}, maximum_errors: {:?} }}", collected_errors, maximum_errors),
+			
+			&Callback(_) => write!(f, "Callback(_)"),
+		}
+	}
+}
+
+impl<'a, DequeuedMessageProcessingError: error::Error> DequeuedMessageProcessingErrorPolicy<'a, DequeuedMessageProcessingError>
+{
+	/// Returns `Ok(())` if dequeuing should continue.
+	#[inline(always)]
+	fn apply(&mut self, compressed_type_identifier: CompressedTypeIdentifier, dequeued_message_processing_error: DequeuedMessageProcessingError) -> Result<(), DequeuedMessageProcessingError>
+	{
+		use self::DequeuedMessageProcessingErrorPolicy::*;
+		
+		match self
+		{
+			&mut StopAndLeaveRemaining => Err(dequeued_message_processing_error),
+			
+			&mut ContinueAndCollectErrors { ref mut collected_errors, maximum_errors } => if collected_errors.len() < maximum_errors
+			{
+				collected_errors.push((compressed_type_identifier, dequeued_message_processing_error));
+				Ok(())
+			}
+			else
+			{
+				Err(dequeued_message_processing_error)
+			},
+			
+			&mut Callback(ref mut callback) =>
+			{
+				callback(compressed_type_identifier, dequeued_message_processing_error);
+				Ok(())
+			}
+		}
+	}
+}
@@ -7,17 +7,50 @@
 /// All implementations of `FixedSizeMessageBody` must share the same `DequeuedMessageProcessingError` when dequeued and processed with `message_handlers.call_and_drop_in_place()`.
 trait Enqueue
 {
-	/// Finds a fixed size message body compressed type identifier for direct use of `enqueue()`.
-	fn fixed_sized_message_body_compressed_type_identifier<FixedSizeMessageBody: 'static + Sized>(&self) -> CompressedTypeIdentifier;
+	/// Finds a fixed size message body compressed type identifier for direct use of `enqueue()`, if `FixedSizeMessageBody` is registered.
+	fn fixed_sized_message_body_compressed_type_identifier<FixedSizeMessageBody: 'static + Sized>(&self) -> Option<CompressedTypeIdentifier>;
 	
 	/// Slow but safe; unnecessary once `fixed_sized_message_body_compressed_type_identifier()` is used.
 	#[inline(always)]
 	fn enqueue_slow_but_safe<FixedSizeMessageBody: 'static + Sized>(&self, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>))
 	{
-		let fixed_sized_message_body_compressed_type_identifier = self.fixed_sized_message_body_compressed_type_identifier::<FixedSizeMessageBody>();
+		let fixed_sized_message_body_compressed_type_identifier = self.fixed_sized_message_body_compressed_type_identifier::<FixedSizeMessageBody>().expect("Unregistered FixedSizeMessageBody");
 		unsafe { self.enqueue(fixed_sized_message_body_compressed_type_identifier, fixed_size_message_body_constructor) }
 	}
 	
-	/// Enqueue a message unsafely.
-	unsafe fn enqueue<FixedSizeMessageBody: Sized>(&self, fixed_sized_message_body_compressed_type_identifier: CompressedTypeIdentifier, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>));
+	/// Enqueue a message unsafely with the highest priority.
+	#[inline(always)]
+	unsafe fn enqueue<FixedSizeMessageBody: Sized>(&self, fixed_sized_message_body_compressed_type_identifier: CompressedTypeIdentifier, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>))
+	{
+		self.enqueue_with_priority(fixed_sized_message_body_compressed_type_identifier, Priority::Highest, fixed_size_message_body_constructor)
+	}
+	
+	/// Enqueue a message unsafely in the priority lane for `priority`.
+	#[inline(always)]
+	unsafe fn enqueue_with_priority<FixedSizeMessageBody: Sized>(&self, fixed_sized_message_body_compressed_type_identifier: CompressedTypeIdentifier, priority: Priority, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>))
+	{
+		self.enqueue_with_trailing_bytes(fixed_sized_message_body_compressed_type_identifier, priority, MessageHeaderExtensions::default(), 0, fixed_size_message_body_constructor)
+	}
+	
+	/// Enqueue a message unsafely with the highest priority if, and only if, there is space available without waiting.
+	///
+	/// If there is not space available, or the message could never fit, `fixed_size_message_body_constructor` is not called and a `TryEnqueueError` handing back `construct_message_arguments` is returned.
+	#[inline(always)]
+	unsafe fn try_enqueue<FixedSizeMessageBody: Sized, ConstructMessageArguments>(&self, fixed_sized_message_body_compressed_type_identifier: CompressedTypeIdentifier, construct_message_arguments: ConstructMessageArguments, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>, ConstructMessageArguments)) -> Result<(), TryEnqueueError<ConstructMessageArguments>>
+	{
+		self.try_enqueue_with_priority(fixed_sized_message_body_compressed_type_identifier, Priority::Highest, construct_message_arguments, fixed_size_message_body_constructor)
+	}
+	
+	/// As for `try_enqueue()`, but in the priority lane for `priority`.
+	#[inline(always)]
+	unsafe fn try_enqueue_with_priority<FixedSizeMessageBody: Sized, ConstructMessageArguments>(&self, fixed_sized_message_body_compressed_type_identifier: CompressedTypeIdentifier, priority: Priority, construct_message_arguments: ConstructMessageArguments, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>, ConstructMessageArguments)) -> Result<(), TryEnqueueError<ConstructMessageArguments>>
+	{
+		self.try_enqueue_with_trailing_bytes(fixed_sized_message_body_compressed_type_identifier, priority, MessageHeaderExtensions::default(), 0, construct_message_arguments, fixed_size_message_body_constructor)
+	}
+	
+	/// Enqueue a message unsafely in the priority lane for `priority` with `message_header_extensions`, reserving `trailing_bytes` immediately after the `FixedSizeMessageBody` for `fixed_size_message_body_constructor` to write to.
+	unsafe fn enqueue_with_trailing_bytes<FixedSizeMessageBody: Sized>(&self, fixed_sized_message_body_compressed_type_identifier: CompressedTypeIdentifier, priority: Priority, message_header_extensions: MessageHeaderExtensions, trailing_bytes: usize, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>));
+	
+	/// As for `try_enqueue_with_priority()`, but with `message_header_extensions` and reserving `trailing_bytes` immediately after the `FixedSizeMessageBody` for `fixed_size_message_body_constructor` to write to.
+	unsafe fn try_enqueue_with_trailing_bytes<FixedSizeMessageBody: Sized, ConstructMessageArguments>(&self, fixed_sized_message_body_compressed_type_identifier: CompressedTypeIdentifier, priority: Priority, message_header_extensions: MessageHeaderExtensions, trailing_bytes: usize, construct_message_arguments: ConstructMessageArguments, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>, ConstructMessageArguments)) -> Result<(), TryEnqueueError<ConstructMessageArguments>>;
 }
@@ -0,0 +1,17 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A message handler panicked whilst handling a message.
+///
+/// Only reported if `QueueSettings.catch_message_handler_panics` is `true`.
+/// The message was dropped in place after the panic was caught and the queue's read position was advanced past it.
+#[derive(Debug)]
+pub struct HandlerPanicked
+{
+	/// The type name of the message (as returned by `std::any::type_name()`).
+	pub type_name: &'static str,
+	
+	/// The panic's payload (as returned by `std::panic::catch_unwind()`).
+	pub payload: Box<dyn Any + Send + 'static>,
+}
@@ -0,0 +1,75 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A least-loaded publisher, which publishes to whichever of a set (`hyper_threads_to_publish_to`) of `HyperThread`s has the emptiest queue.
+///
+/// Load is measured cheaply, without locks, from the number of bytes in each queue as a fraction of its capacity, so may be slightly stale.
+/// Each publish reads the load of every queue; for large sets, consider `PowerOfTwoChoicesPublisher` instead.
+#[derive(Debug)]
+pub struct LeastLoadedPublisher<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>
+{
+	queues: Queues<MessageHandlerArguments, DequeuedMessageProcessingError>,
+	publisher: Publisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>,
+	hyper_threads_to_publish_to: Box<[HyperThread]>,
+}
+
+impl<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> LeastLoadedPublisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>
+{
+	#[inline(always)]
+	fn new(queues: &Queues<MessageHandlerArguments, DequeuedMessageProcessingError>, hyper_threads_to_publish_to: Box<[HyperThread]>) -> Self
+	{
+		queues.assert_hyper_threads_to_publish_to_have_queues(&hyper_threads_to_publish_to);
+		let default_hyper_thread = hyper_threads_to_publish_to.get_unchecked_value_safe(0);
+		
+		Self
+		{
+			queues: queues.clone(),
+			publisher: Publisher::new(queues, default_hyper_thread),
+			hyper_threads_to_publish_to,
+		}
+	}
+	
+	/// Publishes to the hyper thread with the least loaded queue.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish(&self, construct_message_arguments: M::ConstructMessageArguments) -> HyperThread
+	{
+		self.publisher.publish(self.least_loaded_hyper_thread(), construct_message_arguments)
+	}
+	
+	/// Publishes to the hyper thread with the least loaded queue without waiting for space in the queue.
+	///
+	/// Returns the actual hyper thread published to, or, if even the least loaded queue is full, a `PublishError::QueueFull` which hands back the unconsumed `construct_message_arguments`.
+	#[inline(always)]
+	pub fn try_publish(&self, construct_message_arguments: M::ConstructMessageArguments) -> Result<HyperThread, PublishError<M::ConstructMessageArguments>>
+	{
+		self.publisher.try_publish(self.least_loaded_hyper_thread(), construct_message_arguments)
+	}
+	
+	/// Ties are resolved in favour of the hyper thread earliest in `hyper_threads_to_publish_to`.
+	#[inline(always)]
+	fn least_loaded_hyper_thread(&self) -> HyperThread
+	{
+		let mut least_loaded_hyper_thread = self.hyper_threads_to_publish_to.get_unchecked_value_safe(0);
+		let mut least_load = self.queues.load(least_loaded_hyper_thread);
+		
+		for &hyper_thread in self.hyper_threads_to_publish_to.iter().skip(1)
+		{
+			if least_load == 0
+			{
+				break
+			}
+			
+			let load = self.queues.load(hyper_thread);
+			if load < least_load
+			{
+				least_loaded_hyper_thread = hyper_thread;
+				least_load = load;
+			}
+		}
+		
+		least_loaded_hyper_thread
+	}
+}
@@ -7,6 +7,7 @@
 /// Holds state that lives longer than each call to a function closure.
 ///
 /// Can not hold more than 256 functions, but this restriction makes it perform quicker.
+/// If the `wide-compressed-type-identifier` feature is enabled, it can hold up to 65,536 functions in a heap-allocated table instead.
 ///
 /// What do the various type arguments relate to?
 ///
@@ -19,12 +20,17 @@
 /// `MessageHandler` and `FixedSizedMessageBody` are of a different type for each registered function closure.
 ///
 /// A very clever optimization of this structure could produce a jump table at runtime, so reducing indirect calls to direct calls, should this be necessary.
+/// A `MessageSet` registered using `register_message_set()` does this at compile time instead.
 #[derive(Debug)]
 pub struct MessageHandlers<MessageHandlerArguments, MessageHandlerReturns>
 {
-	compressed_type_identifier_to_function: ArrayVec<(MessageHandler<MessageHandlerArguments, MessageHandlerReturns>, DropVariablySizedMessageBodyInPlaceFunctionPointer), CompressedTypeIdentifier::ExclusiveMaximum>,
+	#[cfg(not(feature = "wide-compressed-type-identifier"))] compressed_type_identifier_to_function: ArrayVec<MessageTypeRegistration<MessageHandlerArguments, MessageHandlerReturns>, CompressedTypeIdentifier::ExclusiveMaximum>,
+	#[cfg(feature = "wide-compressed-type-identifier")] compressed_type_identifier_to_function: Vec<MessageTypeRegistration<MessageHandlerArguments, MessageHandlerReturns>>,
 	type_identifier_to_compressed_type_identifier: HashMap<TypeId, CompressedTypeIdentifier>,
 	largest_possible_message: NonZeroU64,
+	dead_letter_sink: Option<Arc<dyn DeadLetterSink>>,
+	static_dispatcher: Option<StaticDispatcher<MessageHandlerArguments, MessageHandlerReturns>>,
+	compact_message_compressed_type_identifier: Option<CompressedTypeIdentifier>,
 }
 
 impl<MessageHandlerArguments, MessageHandlerReturns> Default for MessageHandlers<MessageHandlerArguments, MessageHandlerReturns>
@@ -34,9 +40,13 @@ impl<MessageHandlerArguments, MessageHandlerReturns> Default for MessageHandlers
 	{
 		Self
 		{
-			compressed_type_identifier_to_function: ArrayVec::default(),
-			type_identifier_to_compressed_type_identifier: HashMap::with_capacity(CompressedTypeIdentifier::ExclusiveMaximum),
+			#[cfg(not(feature = "wide-compressed-type-identifier"))] compressed_type_identifier_to_function: ArrayVec::default(),
+			#[cfg(feature = "wide-compressed-type-identifier")] compressed_type_identifier_to_function: Vec::with_capacity(CompressedTypeIdentifier::InitialCapacity),
+			type_identifier_to_compressed_type_identifier: HashMap::with_capacity(CompressedTypeIdentifier::InitialCapacity),
 			largest_possible_message: new_non_zero_u64(MessageRepresentation::smallest_possible_total_message_size_including_message_header() as u64),
+			dead_letter_sink: None,
+			static_dispatcher: None,
+			compact_message_compressed_type_identifier: None,
 		}
 	}
 }
@@ -51,22 +61,175 @@ impl<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> Mess
 	/// There is a 1:1 relationship between `Message` and `CompressedTypeIdentifier`; they are linked through the `Message`'s `TypeId`.
 	///
 	/// If `debug_assertions` are configured, panics if the `MessageHandler` has already been registered.
-	/// If `debug_assertions` are configured, panics if there is not space for more `MessageHandler`s (only 256 message handlers are allowed).
+	/// Panics if there is not space for more `MessageHandler`s (only 256 message handlers are allowed, or 65,536 if the `wide-compressed-type-identifier` feature is enabled).
+	/// Panics if a message of this type, including its header, would be larger than 4 GiB less one byte.
+	/// Panics if a message of this type has an alignment larger than 4096 bytes (a page).
 	///
 	/// A `MessageHandler` does not need to call `drop_in_place()` on `Message`; this will be done when the `MessageHandler` returns.
 	#[inline(always)]
 	pub fn register_message_handler<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>>(&mut self) -> CompressedTypeIdentifier
 	{
-		self.register_message_handler_internal::<M>(M::handle_message)
+		self.register_message_handler_internal::<M>(MessageHandler::new(M::handle_message), Self::drop_in_place_function_pointer::<M>(), MessageTypeRegistration::<MessageHandlerArguments, Result<(), DequeuedMessageProcessingError>>::fixed_size_layout::<M>)
+	}
+	
+	/// Registers all the members of a `MessageSet`, so that their compressed type identifiers are those assigned at compile time, and uses its dispatcher in place of indirect calls.
+	///
+	/// Must be called before any other message handlers are registered, and at most once; panics otherwise.
+	/// Other message handlers can be registered afterwards; these are called indirectly as usual.
+	///
+	/// The dispatcher is not used if message handler panics are caught or there is a `DeadLetterSink`, as then the message must not be dropped in place after a failure.
+	#[inline(always)]
+	pub fn register_message_set<Set: MessageSet<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>>(&mut self)
+	{
+		assert!(self.compressed_type_identifier_to_function.is_empty(), "A MessageSet must be registered first");
+		Set::register_message_handlers(self);
+		self.static_dispatcher = Some(StaticDispatcher::new(Set::handle_message_then_drop_in_place, self.compressed_type_identifier_to_function.len(), TypeId::of::<Set>()))
+	}
+	
+	/// Registers a member, `M`, of a `MessageSet`, `Set`, with the compressed type identifier assigned to it at compile time.
+	///
+	/// Only for use by the implementation of `MessageSet::register_message_handlers()` generated by `message_set!`; use `register_message_set()` instead.
+	///
+	/// Panics if members are not registered in order.
+	#[inline(always)]
+	pub fn register_message_set_member<Set: MessageSet<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, M: 'static + MessageSetMember<Set>>(&mut self)
+	{
+		let compressed_type_identifier = M::compressed_type_identifier();
+		assert_eq!(CompressedTypeIdentifier::next(self.compressed_type_identifier_to_function.len()), compressed_type_identifier, "MessageSet member `{}` registered out of order", type_name::<M>());
+		self.register_message_handler_internal::<M>(MessageHandler::new(M::handle_message), Self::drop_in_place_function_pointer::<M>(), MessageTypeRegistration::<MessageHandlerArguments, Result<(), DequeuedMessageProcessingError>>::fixed_size_layout::<M>);
+	}
+	
+	/// Finds the compressed type identifier of a member, `M`, of a `MessageSet`, `Set`.
+	///
+	/// If `Set` was registered using `register_message_set()`, this is the compressed type identifier assigned at compile time and there is no look up.
+	#[inline(always)]
+	pub(crate) fn find_message_set_member_compressed_type_identifier<Set: MessageSet<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, M: 'static + MessageSetMember<Set>>(&self) -> Option<CompressedTypeIdentifier>
+	{
+		match self.static_dispatcher
+		{
+			Some(ref static_dispatcher) if static_dispatcher.is_for(TypeId::of::<Set>()) => Some(M::compressed_type_identifier()),
+			
+			_ => self.find_fixed_size_message_body_compressed_type_identifier::<M>(),
+		}
+	}
+	
+	/// Registers a `message_handler` for values of `FixedSizeMessageBody` and returns a `CompressedTypeIdentifier` to refer to it.
+	///
+	/// `FixedSizeMessageBody` does not need to implement `Message`; values are moved into a queue by a `ValuePublisher` and so are never constructed in place.
+	/// `message_handler` can be a closure capturing state, which is moved to the heap; it must be `Send`, as the queue, and so `message_handler`, may be dropped on a different thread to the one it is called on.
+	///
+	/// Otherwise as for `register_message_handler()`.
+	#[inline(always)]
+	pub fn register_value_message_handler<FixedSizeMessageBody: 'static + Sized>(&mut self, message_handler: impl Fn(&mut FixedSizeMessageBody, &MessageHandlerArguments) -> Result<(), DequeuedMessageProcessingError> + Send + 'static) -> CompressedTypeIdentifier
+	{
+		self.register_message_handler_internal::<FixedSizeMessageBody>(MessageHandler::new_boxed(message_handler), Self::drop_in_place_function_pointer::<FixedSizeMessageBody>(), MessageTypeRegistration::<MessageHandlerArguments, Result<(), DequeuedMessageProcessingError>>::fixed_size_layout::<FixedSizeMessageBody>)
+	}
+	
+	/// Registers a `Message`, `M`, published by a `ConflatingPublisher` with keys of type `Key`, and returns a `CompressedTypeIdentifier` to refer to it.
+	///
+	/// Otherwise as for `register_message_handler()`.
+	#[inline(always)]
+	pub fn register_conflated_message_handler<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, Key: 'static + Hash + Eq>(&mut self) -> CompressedTypeIdentifier
+	{
+		self.register_message_handler_internal::<ConflatedMessage<M, Key>>(MessageHandler::new(ConflatedMessage::<M, Key>::handle_message), Self::drop_in_place_function_pointer::<ConflatedMessage<M, Key>>(), MessageTypeRegistration::<MessageHandlerArguments, Result<(), DequeuedMessageProcessingError>>::fixed_size_layout::<ConflatedMessage<M, Key>>)
+	}
+	
+	/// Registers a `message_handler` for boxed values of `T` published with an opcode by a `CompactPublisher`, and returns a `CompressedTypeIdentifier` to refer to it.
+	///
+	/// `message_handler` is handed off the `Box<T>` and its opcode, which can be used to distinguish different operations on the same type.
+	/// Only one type can be registered; it is the only type that can use a queue's compact lane, as the tag bits of a message in the compact lane are its opcode.
+	///
+	/// Panics if a type has already been registered.
+	/// Otherwise as for `register_value_message_handler()`.
+	#[inline(always)]
+	pub fn register_compact_message_handler<T: 'static>(&mut self, message_handler: impl Fn(Box<T>, u32, &MessageHandlerArguments) -> Result<(), DequeuedMessageProcessingError> + Send + 'static) -> CompressedTypeIdentifier
+	{
+		assert!(self.compact_message_compressed_type_identifier.is_none(), "A compact message handler has already been registered");
+		
+		let compressed_type_identifier = self.register_message_handler_internal::<CompactMessage<T>>(MessageHandler::new_boxed(move |compact_message: &mut CompactMessage<T>, message_handler_arguments: &MessageHandlerArguments| compact_message.handle_message(&message_handler, message_handler_arguments)), Self::drop_in_place_function_pointer::<CompactMessage<T>>(), MessageTypeRegistration::<MessageHandlerArguments, Result<(), DequeuedMessageProcessingError>>::fixed_size_layout::<CompactMessage<T>>);
+		self.compact_message_compressed_type_identifier = Some(compressed_type_identifier);
+		compressed_type_identifier
+	}
+	
+	/// Registers a `VariablySizedMessage` and returns a `CompressedTypeIdentifier` to refer to it.
+	///
+	/// Otherwise as for `register_message_handler()`.
+	///
+	/// The largest possible message used to size queues does not include the variably sized payload; choose `QueueSettings.preferred_maximum_number_of_elements_of_largest_possible_fixed_size_message_body` with this in mind.
+	#[inline(always)]
+	pub fn register_variably_sized_message_handler<Header: 'static + VariablySizedMessage<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>>(&mut self) -> CompressedTypeIdentifier
+	{
+		self.register_message_handler_internal::<HeaderAndPayload<Header>>(MessageHandler::new(HeaderAndPayload::<Header>::handle_message), Self::drop_in_place_function_pointer::<HeaderAndPayload<Header>>(), HeaderAndPayload::<Header>::layout)
+	}
+	
+	/// Registers a `VariablySizedStrMessage` and returns a `CompressedTypeIdentifier` to refer to it.
+	///
+	/// Publish it using a `StrPublisher`.
+	#[inline(always)]
+	pub fn register_variably_sized_str_message_handler<Header: 'static + VariablySizedStrMessage<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>>(&mut self) -> CompressedTypeIdentifier
+	{
+		self.register_variably_sized_message_handler::<StrHeader<Header>>()
+	}
+	
+	/// Registers a `TraitObjectMessage`, eg `dyn MyTrait`, and returns a `CompressedTypeIdentifier` to refer to it.
+	///
+	/// Values of any type implementing `MyTrait` can then be published using a `TraitObjectPublisher`; their types do not need to be registered.
+	///
+	/// Otherwise as for `register_message_handler()`.
+	///
+	/// The largest possible message used to size queues does not include the value; choose `QueueSettings.preferred_maximum_number_of_elements_of_largest_possible_fixed_size_message_body` with this in mind.
+	#[inline(always)]
+	pub fn register_trait_object_message_handler<Trait: ?Sized + TraitObjectMessage<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>>(&mut self) -> CompressedTypeIdentifier
+	{
+		self.register_message_handler_internal::<TraitObjectMessageBody<Trait, ()>>(MessageHandler::new(TraitObjectMessageBody::<Trait, ()>::handle_message), TraitObjectMessageBody::<Trait, ()>::drop_in_place, TraitObjectMessageBody::<Trait, ()>::layout)
+	}
+	
+	/// Calls the function registered for this compressed type identifier, optionally catching any panic.
+	///
+	/// Afterwards, if the function failed or panicked and there is a `DeadLetterSink`, the message is moved to it; otherwise the message is dropped in place.
+	///
+	/// Panics if no function is registered (only if `debug_assertions` are configured).
+	#[inline(always)]
+	pub(crate) fn call_then_drop_in_place_or_dead_letter(&self, compressed_type_identifier: CompressedTypeIdentifier, variably_sized_message_body: NonNull<VariablySizedMessageBody>, message_handler_arguments: &MessageHandlerArguments, catch_message_handler_panics: bool) -> Result<Result<(), DequeuedMessageProcessingError>, HandlerPanicked>
+	{
+		let message_type_registration = self.entry(compressed_type_identifier);
+		
+		let result = if catch_message_handler_panics
+		{
+			catch_unwind(AssertUnwindSafe(|| message_type_registration.message_handler.call(variably_sized_message_body, message_handler_arguments)))
+		}
+		else
+		{
+			Ok(message_type_registration.message_handler.call(variably_sized_message_body, message_handler_arguments))
+		};
+		
+		use self::DeadLetterReason::*;
+		let dead_letter_reason = match result
+		{
+			Ok(Ok(())) => None,
+			
+			Ok(Err(_)) => Some(MessageHandlerFailed),
+			
+			Err(_) => Some(MessageHandlerPanicked),
+		};
+		
+		match (dead_letter_reason, &self.dead_letter_sink)
+		{
+			(Some(dead_letter_reason), &Some(ref dead_letter_sink)) => dead_letter_sink.receive_dead_letter(dead_letter_reason, DeadLetter::move_from(compressed_type_identifier, message_type_registration, variably_sized_message_body)),
+			
+			_ => Self::drop_message(&message_type_registration.drop_in_place_function_pointer, variably_sized_message_body),
+		}
+		
+		result.map_err(|payload| HandlerPanicked { type_name: message_type_registration.type_name, payload })
 	}
 }
 
 impl<MessageHandlerArguments, MessageHandlerReturns> MessageHandlers<MessageHandlerArguments, MessageHandlerReturns>
 {
 	#[inline(always)]
-	fn register_message_handler_internal<FixedSizedMessageBody: 'static + Sized>(&mut self, message_handler: fn(&mut FixedSizedMessageBody, &MessageHandlerArguments) -> MessageHandlerReturns) -> CompressedTypeIdentifier
+	fn register_message_handler_internal<FixedSizedMessageBody: 'static + Sized>(&mut self, message_handler: MessageHandler<MessageHandlerArguments, MessageHandlerReturns>, drop_in_place_function_pointer: DropVariablySizedMessageBodyInPlaceFunctionPointer, layout_function_pointer: fn(NonNull<VariablySizedMessageBody>) -> Layout) -> CompressedTypeIdentifier
 	{
-		let next_compressed_type_identifier = CompressedTypeIdentifier::next(&self.compressed_type_identifier_to_function);
+		let next_compressed_type_identifier = CompressedTypeIdentifier::next(self.compressed_type_identifier_to_function.len());
 		
 		{
 			let type_identifier = TypeId::of::<FixedSizedMessageBody>();
@@ -74,16 +237,24 @@ impl<MessageHandlerArguments, MessageHandlerReturns> MessageHandlers<MessageHand
 			debug_assert!(previous.is_none(), "Duplicate registration");
 		};
 		
-		let drop_in_place_function_pointer =
-		{
-			let virtual_method_table_pointer = VirtualMethodTablePointer::from_any::<FixedSizedMessageBody>();
-			unsafe { transmute(virtual_method_table_pointer.drop_in_place_function_pointer()) }
-		};
-		
-		self.compressed_type_identifier_to_function.push((MessageHandler::new(message_handler), drop_in_place_function_pointer));
+		self.compressed_type_identifier_to_function.push
+		(
+			MessageTypeRegistration
+			{
+				message_handler,
+				drop_in_place_function_pointer,
+				type_name: type_name::<FixedSizedMessageBody>(),
+				type_identifier: TypeId::of::<FixedSizedMessageBody>(),
+				layout_function_pointer,
+				expired_message_handler: None,
+			}
+		);
 		
 		{
-			let largest_possible_message = new_non_zero_u64(MessageRepresentation::largest_possible_total_message_size_including_message_header::<FixedSizedMessageBody>() as u64);
+			let largest_possible_total_message_size_including_message_header = MessageRepresentation::largest_possible_total_message_size_including_message_header::<FixedSizedMessageBody>();
+			assert!(align_of::<FixedSizedMessageBody>() <= MessageRepresentation::MaximumMessageBodyAlignment, "FixedSizedMessageBody `{}` is aligned to more than a page", type_name::<FixedSizedMessageBody>());
+			assert!(largest_possible_total_message_size_including_message_header <= MessageRepresentation::MaximumTotalMessageSize, "FixedSizedMessageBody `{}` is too large for a message", type_name::<FixedSizedMessageBody>());
+			let largest_possible_message = new_non_zero_u64(largest_possible_total_message_size_including_message_header as u64);
 			if largest_possible_message > self.largest_possible_message
 			{
 				self.largest_possible_message = largest_possible_message
@@ -93,6 +264,68 @@ impl<MessageHandlerArguments, MessageHandlerReturns> MessageHandlers<MessageHand
 		next_compressed_type_identifier
 	}
 	
+	#[inline(always)]
+	fn drop_in_place_function_pointer<FixedSizedMessageBody: 'static + Sized>() -> DropVariablySizedMessageBodyInPlaceFunctionPointer
+	{
+		let virtual_method_table_pointer = VirtualMethodTablePointer::from_any::<FixedSizedMessageBody>();
+		unsafe { transmute(virtual_method_table_pointer.drop_in_place_function_pointer()) }
+	}
+	
+	/// Registers a hook called with a message which had expired by the time it was dequeued, instead of its message handler.
+	///
+	/// The message is dropped in place after the hook returns.
+	/// If `QueueSettings.catch_message_handler_panics` is `true`, a panic in the hook is caught as for a message handler.
+	/// As for `register_value_message_handler()`, `expired_message_handler` can be a closure capturing state, eg to count expired messages.
+	///
+	/// Panics if no message handler has been registered for `FixedSizeMessageBody`.
+	#[inline(always)]
+	pub fn register_expired_message_handler<FixedSizeMessageBody: 'static + Sized>(&mut self, expired_message_handler: impl Fn(&mut FixedSizeMessageBody, &MessageHandlerArguments) + Send + 'static)
+	{
+		let compressed_type_identifier = self.find_fixed_size_message_body_compressed_type_identifier::<FixedSizeMessageBody>().expect("Unregistered FixedSizeMessageBody");
+		let message_type_registration = self.compressed_type_identifier_to_function.get_mut(compressed_type_identifier.index()).unwrap();
+		message_type_registration.expired_message_handler = Some(MessageHandler::new_boxed(expired_message_handler))
+	}
+	
+	/// Sets a sink for messages which could not be handled; these are then moved to it rather than being dropped in place.
+	///
+	/// The same sink can be shared by the `MessageHandlers` of several queues.
+	#[inline(always)]
+	pub fn set_dead_letter_sink(&mut self, dead_letter_sink: Arc<dyn DeadLetterSink>)
+	{
+		self.dead_letter_sink = Some(dead_letter_sink)
+	}
+	
+	/// The compressed type identifier of the `CompactMessage<T>` registered using `register_compact_message_handler()`, if any.
+	#[inline(always)]
+	pub(crate) fn compact_message_compressed_type_identifier(&self) -> Option<CompressedTypeIdentifier>
+	{
+		self.compact_message_compressed_type_identifier
+	}
+	
+	#[inline(always)]
+	pub(crate) fn has_dead_letter_sink(&self) -> bool
+	{
+		self.dead_letter_sink.is_some()
+	}
+	
+	/// If there is a dead letter sink, constructs a message of an unregistered type, followed by `trailing_bytes`, on the heap and moves it to the sink.
+	///
+	/// Returns `Err(construct_message_arguments)` if there is no dead letter sink; the message is then never constructed.
+	#[inline(always)]
+	pub(crate) fn dead_letter_unregistered<FixedSizeMessageBody: 'static + Sized, ConstructMessageArguments>(&self, trailing_bytes: usize, construct_message_arguments: ConstructMessageArguments, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>, ConstructMessageArguments)) -> Result<(), ConstructMessageArguments>
+	{
+		match self.dead_letter_sink
+		{
+			None => Err(construct_message_arguments),
+			
+			Some(ref dead_letter_sink) =>
+			{
+				dead_letter_sink.receive_dead_letter(DeadLetterReason::Unregistered, DeadLetter::construct(trailing_bytes, |uninitialized_memory| fixed_size_message_body_constructor(uninitialized_memory, construct_message_arguments)));
+				Ok(())
+			}
+		}
+	}
+	
 	/// Finds a compressed type identifier for a given type.
 	///
 	/// Slow as it uses a HashMap look up; do not do this on the critical path.
@@ -106,7 +339,7 @@ impl<MessageHandlerArguments, MessageHandlerReturns> MessageHandlers<MessageHand
 	#[inline(always)]
 	pub(crate) fn queue_size_in_bytes(&self, preferred_maximum_number_of_elements_of_largest_possible_fixed_size_message_body: NonZeroU64) -> NonZeroU64
 	{
-		new_non_zero_u64(self.largest_possible_message.get() * preferred_maximum_number_of_elements_of_largest_possible_fixed_size_message_body.get())
+		new_non_zero_u64(self.largest_possible_message.get().checked_mul(preferred_maximum_number_of_elements_of_largest_possible_fixed_size_message_body.get()).expect("Queue would be far too large"))
 	}
 	
 	/// Calls the function registered for this compressed type identifier.
@@ -117,12 +350,20 @@ impl<MessageHandlerArguments, MessageHandlerReturns> MessageHandlers<MessageHand
 	#[inline(always)]
 	pub(crate) fn call_and_drop_in_place(&self, compressed_type_identifier: CompressedTypeIdentifier, variably_sized_message_body: NonNull<VariablySizedMessageBody>, message_handler_arguments: &MessageHandlerArguments) -> MessageHandlerReturns
 	{
-		let (message_handler, drop_in_place_function_pointer) = self.entry(compressed_type_identifier);
-		let result = message_handler.call(variably_sized_message_body, message_handler_arguments);
-		Self::drop_message(drop_in_place_function_pointer, variably_sized_message_body);
+		if let Some(ref static_dispatcher) = self.static_dispatcher
+		{
+			if static_dispatcher.dispatches(compressed_type_identifier)
+			{
+				return static_dispatcher.call_and_drop_in_place(compressed_type_identifier, variably_sized_message_body, message_handler_arguments)
+			}
+		}
+		
+		let message_type_registration = self.entry(compressed_type_identifier);
+		let result = message_type_registration.message_handler.call(variably_sized_message_body, message_handler_arguments);
+		Self::drop_message(&message_type_registration.drop_in_place_function_pointer, variably_sized_message_body);
 		result
 	}
-
+	
 	/// Calls the drop in place function registered for this compressed type identifier.
 	///
 	/// `variably_sized_message_body` has a known size if `compressed_type_identifier` is known.
@@ -131,8 +372,44 @@ impl<MessageHandlerArguments, MessageHandlerReturns> MessageHandlers<MessageHand
 	#[inline(always)]
 	pub(crate) fn drop_in_place(&self, compressed_type_identifier: CompressedTypeIdentifier, variably_sized_message_body: NonNull<VariablySizedMessageBody>)
 	{
-		let (_message_handler, drop_in_place_function_pointer) = self.entry(compressed_type_identifier);
-		Self::drop_message(drop_in_place_function_pointer, variably_sized_message_body)
+		let message_type_registration = self.entry(compressed_type_identifier);
+		Self::drop_message(&message_type_registration.drop_in_place_function_pointer, variably_sized_message_body)
+	}
+	
+	/// The layout of the message body registered for this compressed type identifier.
+	///
+	/// Panics if no function is registered (only if `debug_assertions` are configured).
+	#[inline(always)]
+	pub(crate) fn layout(&self, compressed_type_identifier: CompressedTypeIdentifier, variably_sized_message_body: NonNull<VariablySizedMessageBody>) -> Layout
+	{
+		self.entry(compressed_type_identifier).layout(variably_sized_message_body)
+	}
+	
+	/// Calls the expired message handler, if any, registered for this compressed type identifier, optionally catching any panic, then the drop in place function.
+	///
+	/// The message is dropped in place even if the expired message handler panicked and its panic was caught.
+	///
+	/// Panics if no function is registered (only if `debug_assertions` are configured).
+	#[inline(always)]
+	pub(crate) fn call_expired_message_handler_and_drop_in_place(&self, compressed_type_identifier: CompressedTypeIdentifier, variably_sized_message_body: NonNull<VariablySizedMessageBody>, message_handler_arguments: &MessageHandlerArguments, catch_message_handler_panics: bool) -> Result<(), HandlerPanicked>
+	{
+		let message_type_registration = self.entry(compressed_type_identifier);
+		let result = match message_type_registration.expired_message_handler
+		{
+			None => Ok(()),
+			
+			Some(ref expired_message_handler) => if catch_message_handler_panics
+			{
+				catch_unwind(AssertUnwindSafe(|| expired_message_handler.call(variably_sized_message_body, message_handler_arguments)))
+			}
+			else
+			{
+				Ok(expired_message_handler.call(variably_sized_message_body, message_handler_arguments))
+			},
+		};
+		Self::drop_message(&message_type_registration.drop_in_place_function_pointer, variably_sized_message_body);
+		
+		result.map_err(|payload| HandlerPanicked { type_name: message_type_registration.type_name, payload })
 	}
 	
 	#[inline(always)]
@@ -145,7 +422,7 @@ impl<MessageHandlerArguments, MessageHandlerReturns> MessageHandlers<MessageHand
 	///
 	/// Panics if no function is registered (only if `debug_assertions` are configured).
 	#[inline(always)]
-	fn entry(&self, compressed_type_identifier: CompressedTypeIdentifier) -> &(MessageHandler<MessageHandlerArguments, MessageHandlerReturns>, DropVariablySizedMessageBodyInPlaceFunctionPointer)
+	fn entry(&self, compressed_type_identifier: CompressedTypeIdentifier) -> &MessageTypeRegistration<MessageHandlerArguments, MessageHandlerReturns>
 	{
 		let index = compressed_type_identifier.index();
 
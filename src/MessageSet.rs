@@ -0,0 +1,25 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A set of `Message` types, each with a compressed type identifier assigned at compile time, and a dispatcher that calls their message handlers using a `match` rather than an indirect call.
+///
+/// The compiler can then inline hot message handlers.
+///
+/// Implement using the `message_set!` macro; register using `MessageHandlers::register_message_set()` and publish using `Queues::message_set_publisher()`.
+pub trait MessageSet: 'static
+{
+	/// Message handler arguments common to all members.
+	type MessageHandlerArguments;
+	
+	/// Error common to all members.
+	type DequeuedMessageProcessingError: error::Error;
+	
+	/// Registers all members in order, so that the first member is given the compressed type identifier `0`, the second `1`, and so on.
+	fn register_message_handlers(message_handlers: &mut MessageHandlers<Self::MessageHandlerArguments, Result<(), Self::DequeuedMessageProcessingError>>);
+	
+	/// Calls `handle_message()` on the member with the index `compressed_type_identifier_index` then drops it in place.
+	///
+	/// `message_body` must point to an instance of that member.
+	unsafe fn handle_message_then_drop_in_place(compressed_type_identifier_index: usize, message_body: NonNull<u8>, message_handler_arguments: &Self::MessageHandlerArguments) -> Result<(), Self::DequeuedMessageProcessingError>;
+}
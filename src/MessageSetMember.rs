@@ -0,0 +1,21 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A member of a `MessageSet`.
+///
+/// Implemented by the `message_set!` macro.
+pub trait MessageSetMember<Set: MessageSet>: Message<MessageHandlerArguments=Set::MessageHandlerArguments, DequeuedMessageProcessingError=Set::DequeuedMessageProcessingError>
+{
+	/// Index (position) in the `MessageSet`.
+	const CompressedTypeIdentifierIndex: usize;
+	
+	/// Compressed type identifier, known at compile time without a look up.
+	///
+	/// Used when registering and by publishers created with `Queues::message_set_publisher()`.
+	#[inline(always)]
+	fn compressed_type_identifier() -> CompressedTypeIdentifier
+	{
+		CompressedTypeIdentifier::next(Self::CompressedTypeIdentifierIndex)
+	}
+}
@@ -0,0 +1,57 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A time on the monotonic clock (`CLOCK_MONOTONIC`), in nanoseconds.
+///
+/// Used as the due time of a scheduled message; it is consistent across all threads of a process.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MonotonicTime(u64);
+
+impl MonotonicTime
+{
+	/// Now.
+	#[inline(always)]
+	pub fn now() -> Self
+	{
+		let mut time = unsafe_uninitialized::<timespec>();
+		let result = unsafe { clock_gettime(CLOCK_MONOTONIC, &mut time) };
+		debug_assert_eq!(result, 0, "clock_gettime(CLOCK_MONOTONIC) failed");
+		Self((time.tv_sec as u64) * 1_000_000_000 + (time.tv_nsec as u64))
+	}
+	
+	/// `duration` after now.
+	#[inline(always)]
+	pub fn after(duration: Duration) -> Self
+	{
+		Self::now().saturating_add(duration)
+	}
+	
+	/// Adds `duration`, saturating at the largest possible time.
+	#[inline(always)]
+	pub fn saturating_add(self, duration: Duration) -> Self
+	{
+		let nanoseconds = duration.as_nanos();
+		Self(if nanoseconds > (u64::MAX as u128)
+		{
+			u64::MAX
+		}
+		else
+		{
+			self.0.saturating_add(nanoseconds as u64)
+		})
+	}
+	
+	/// Nanoseconds since an unspecified point in the past (usually boot).
+	#[inline(always)]
+	pub fn nanoseconds(self) -> u64
+	{
+		self.0
+	}
+	
+	#[inline(always)]
+	fn from_nanoseconds(nanoseconds: u64) -> Self
+	{
+		Self(nanoseconds)
+	}
+}
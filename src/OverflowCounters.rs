@@ -0,0 +1,70 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// Counters of the number of times an `OverflowPolicy` has been applied to a queue.
+///
+/// Counters are monotonically increasing and are updated with relaxed memory ordering; they are suitable for telemetry but not for synchronization.
+#[derive(Default, Debug)]
+pub struct OverflowCounters
+{
+	blocked: AtomicU64,
+	dropped_newest: AtomicU64,
+	dropped_oldest: AtomicU64,
+	spilled_to_heap: AtomicU64,
+}
+
+impl OverflowCounters
+{
+	/// Number of messages whose publisher had to wait for space (`OverflowPolicy::Block`).
+	#[inline(always)]
+	pub fn blocked(&self) -> u64
+	{
+		self.blocked.load(Relaxed)
+	}
+	
+	/// Number of messages discarded when published (`OverflowPolicy::DropNewest`).
+	#[inline(always)]
+	pub fn dropped_newest(&self) -> u64
+	{
+		self.dropped_newest.load(Relaxed)
+	}
+	
+	/// Number of messages evicted from the magic ring buffer (`OverflowPolicy::DropOldest`).
+	#[inline(always)]
+	pub fn dropped_oldest(&self) -> u64
+	{
+		self.dropped_oldest.load(Relaxed)
+	}
+	
+	/// Number of messages spilled to the heap (`OverflowPolicy::DropOldest` and `OverflowPolicy::SpillToHeap`).
+	#[inline(always)]
+	pub fn spilled_to_heap(&self) -> u64
+	{
+		self.spilled_to_heap.load(Relaxed)
+	}
+	
+	#[inline(always)]
+	fn increment_blocked(&self)
+	{
+		self.blocked.fetch_add(1, Relaxed);
+	}
+	
+	#[inline(always)]
+	fn increment_dropped_newest(&self)
+	{
+		self.dropped_newest.fetch_add(1, Relaxed);
+	}
+	
+	#[inline(always)]
+	fn increment_dropped_oldest(&self)
+	{
+		self.dropped_oldest.fetch_add(1, Relaxed);
+	}
+	
+	#[inline(always)]
+	fn increment_spilled_to_heap(&self)
+	{
+		self.spilled_to_heap.fetch_add(1, Relaxed);
+	}
+}
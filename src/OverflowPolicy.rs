@@ -0,0 +1,67 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// What to do when a message is published to a queue whose magic ring buffer is full.
+///
+/// Only applies to `publish()` and `publish_safe_but_slow()`; `try_publish()` and `try_publish_safe_but_slow()` always return `PublishError::QueueFull` instead.
+///
+/// Each time an overflow policy is applied it is counted in the queue's `OverflowCounters`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum OverflowPolicy
+{
+	/// Wait for space in the magic ring buffer.
+	///
+	/// Spins, then yields the thread, then spins again, and so on.
+	/// Suitable for control-plane traffic which must not be lost.
+	///
+	/// Panics if the publishing thread is the queue's subscriber (eg when publishing to itself), as it would otherwise wait forever for space only it can make.
+	Block
+	{
+		/// Number of spins before yielding the thread to the operating system scheduler.
+		maximum_spins_before_yielding: u32,
+	},
+	
+	/// Discard the message being published; it is never constructed.
+	DropNewest,
+	
+	/// Discard the oldest messages in the magic ring buffer, running their registered drop-in-place functions, to make up for the message being published.
+	///
+	/// As only the subscriber may read from the magic ring buffer, the message being published is spilled to the heap and the oldest messages are evicted the next time the subscriber dequeues.
+	/// Whilst messages are spilled, every message published is spilled and makes up for itself by evicting the oldest messages, first from the magic ring buffer then from the heap.
+	/// So that the heap does not grow without bound even if the subscriber does not dequeue, the oldest messages spilled to the heap are evicted when publishing whilst they hold more bytes than the magic ring buffer can.
+	DropOldest,
+	
+	/// Park the message being published in a per-queue overflow list on the heap.
+	///
+	/// The subscriber dequeues messages in the overflow list after those in the magic ring buffer.
+	SpillToHeap,
+}
+
+impl Default for OverflowPolicy
+{
+	#[inline(always)]
+	fn default() -> Self
+	{
+		OverflowPolicy::Block
+		{
+			maximum_spins_before_yielding: 64,
+		}
+	}
+}
+
+impl OverflowPolicy
+{
+	#[inline(always)]
+	fn spills(self) -> bool
+	{
+		use self::OverflowPolicy::*;
+		
+		match self
+		{
+			Block { .. } | DropNewest => false,
+			
+			DropOldest | SpillToHeap => true,
+		}
+	}
+}
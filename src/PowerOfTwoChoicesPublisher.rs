@@ -0,0 +1,95 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A power-of-two-choices publisher, which chooses two of a set (`hyper_threads_to_publish_to`) of `HyperThread`s at random and publishes to the one with the less loaded queue.
+///
+/// This avoids reading the load of every queue, as `LeastLoadedPublisher` does, yet still spreads load almost as evenly.
+/// It also avoids every publisher piling on to the same momentarily emptiest queue.
+///
+/// Random choices are made using a xorshift pseudo-random number generator, seeded from the time stamp counter.
+#[derive(Debug)]
+pub struct PowerOfTwoChoicesPublisher<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>
+{
+	queues: Queues<MessageHandlerArguments, DequeuedMessageProcessingError>,
+	publisher: Publisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>,
+	hyper_threads_to_publish_to: Box<[HyperThread]>,
+	xorshift_state: Cell<u64>,
+}
+
+impl<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> PowerOfTwoChoicesPublisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>
+{
+	#[inline(always)]
+	fn new(queues: &Queues<MessageHandlerArguments, DequeuedMessageProcessingError>, hyper_threads_to_publish_to: Box<[HyperThread]>) -> Self
+	{
+		queues.assert_hyper_threads_to_publish_to_have_queues(&hyper_threads_to_publish_to);
+		let default_hyper_thread = hyper_threads_to_publish_to.get_unchecked_value_safe(0);
+		
+		Self
+		{
+			queues: queues.clone(),
+			publisher: Publisher::new(queues, default_hyper_thread),
+			hyper_threads_to_publish_to,
+			xorshift_state: Cell::new(read_time_stamp_counter() | 1),
+		}
+	}
+	
+	/// Publishes to the less loaded of two randomly chosen hyper threads.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish(&self, construct_message_arguments: M::ConstructMessageArguments) -> HyperThread
+	{
+		self.publisher.publish(self.less_loaded_of_two_choices(), construct_message_arguments)
+	}
+	
+	/// Publishes to the less loaded of two randomly chosen hyper threads without waiting for space in the queue.
+	///
+	/// Returns the actual hyper thread published to, or, if its queue is full, a `PublishError::QueueFull` which hands back the unconsumed `construct_message_arguments`.
+	#[inline(always)]
+	pub fn try_publish(&self, construct_message_arguments: M::ConstructMessageArguments) -> Result<HyperThread, PublishError<M::ConstructMessageArguments>>
+	{
+		self.publisher.try_publish(self.less_loaded_of_two_choices(), construct_message_arguments)
+	}
+	
+	#[inline(always)]
+	fn less_loaded_of_two_choices(&self) -> HyperThread
+	{
+		let length = self.hyper_threads_to_publish_to.len();
+		if length == 1
+		{
+			return self.hyper_threads_to_publish_to.get_unchecked_value_safe(0)
+		}
+		
+		let first_index = (self.next_random() % (length as u64)) as usize;
+		let second_index =
+		{
+			let offset = 1 + (self.next_random() % ((length - 1) as u64)) as usize;
+			(first_index + offset) % length
+		};
+		
+		let first_hyper_thread = self.hyper_threads_to_publish_to.get_unchecked_value_safe(first_index);
+		let second_hyper_thread = self.hyper_threads_to_publish_to.get_unchecked_value_safe(second_index);
+		
+		if self.queues.load(second_hyper_thread) < self.queues.load(first_hyper_thread)
+		{
+			second_hyper_thread
+		}
+		else
+		{
+			first_hyper_thread
+		}
+	}
+	
+	/// xorshift64.
+	#[inline(always)]
+	fn next_random(&self) -> u64
+	{
+		let mut x = self.xorshift_state.get();
+		x ^= x << 13;
+		x ^= x >> 7;
+		x ^= x << 17;
+		self.xorshift_state.set(x);
+		x
+	}
+}
@@ -0,0 +1,23 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// The priority with which a message is published.
+///
+/// `0` is the highest priority; larger values are lower priorities.
+/// If a queue has fewer priority lanes than a priority needs, the message is published to the queue's lowest priority lane.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Priority(pub u8);
+
+impl Priority
+{
+	/// The highest priority, used by publishers which do not specify a priority.
+	pub const Highest: Self = Priority(0);
+	
+	#[inline(always)]
+	fn priority_lane_index(self, number_of_priority_lanes: usize) -> usize
+	{
+		debug_assert_ne!(number_of_priority_lanes, 0);
+		min(self.0 as usize, number_of_priority_lanes - 1)
+	}
+}
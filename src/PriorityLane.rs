@@ -0,0 +1,269 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// One priority lane of a `Queue`.
+///
+/// Each priority lane has its own magic ring buffer and its own overflow list of messages spilled to the heap; messages are first-in, first-out within a priority lane.
+#[derive(Debug)]
+struct PriorityLane
+{
+	magic_ring_buffer: MagicRingBuffer,
+	queue_occupancy: QueueOccupancy,
+	overflow: Mutex<VecDeque<SpilledMessage>>,
+	overflow_is_not_empty: AtomicBool,
+	bytes_spilled: AtomicU64,
+	bytes_of_oldest_messages_to_evict: AtomicU64,
+}
+
+impl PriorityLane
+{
+	#[inline(always)]
+	fn new(defaults: &DefaultHugePageSizes, queue_size_in_bytes: NonZeroU64, inclusive_maximum_bytes_wasted: u64) -> Result<Self, MirroredMemoryMapCreationError>
+	{
+		Ok
+		(
+			Self
+			{
+				magic_ring_buffer: MagicRingBuffer::allocate(defaults, queue_size_in_bytes, inclusive_maximum_bytes_wasted)?,
+				queue_occupancy: QueueOccupancy::new(queue_size_in_bytes),
+				overflow: Mutex::new(VecDeque::new()),
+				overflow_is_not_empty: AtomicBool::new(false),
+				bytes_spilled: AtomicU64::new(0),
+				bytes_of_oldest_messages_to_evict: AtomicU64::new(0),
+			}
+		)
+	}
+	
+	/// Processes the next message, if any, in the magic ring buffer or, if that is empty and no space in it is still reserved, the overflow list.
+	///
+	/// Otherwise, a message spilled after space was reserved for an earlier message which is still being written would be handled first.
+	///
+	/// Returns the number of bytes dequeued if there was a message.
+	#[inline(always)]
+	fn process_next_message<DequeuedMessageProcessingError>(&self, message_processor: &mut impl FnMut(CompressedTypeIdentifier, MessageHeaderExtensions, NonNull<VariablySizedMessageBody>) -> Result<(), DequeuedMessageProcessingError>) -> Result<Option<usize>, DequeuedMessageProcessingError>
+	{
+		let (_more_data_to_read, bytes_dequeued) = self.process_next_message_in_magic_ring_buffer(&mut *message_processor)?;
+		if bytes_dequeued != 0
+		{
+			return Ok(Some(bytes_dequeued))
+		}
+		
+		// Space is still reserved for a message which is being written; it was published before any message spilled since.
+		if self.queue_occupancy.is_not_empty()
+		{
+			return Ok(None)
+		}
+		
+		match self.pop_spilled_message()
+		{
+			None => Ok(None),
+			
+			Some(mut spilled_message) =>
+			{
+				spilled_message.process(&mut *message_processor)?;
+				Ok(Some(spilled_message.total_message_size()))
+			}
+		}
+	}
+	
+	/// Processes the next message, if any, in the magic ring buffer, and releases the bytes it occupied.
+	///
+	/// Returns `(more_data_to_read, bytes_dequeued)`.
+	#[inline(always)]
+	fn process_next_message_in_magic_ring_buffer<DequeuedMessageProcessingError>(&self, mut message_processor: impl FnMut(CompressedTypeIdentifier, MessageHeaderExtensions, NonNull<VariablySizedMessageBody>) -> Result<(), DequeuedMessageProcessingError>) -> Result<(bool, usize), DequeuedMessageProcessingError>
+	{
+		let mut bytes_dequeued = 0;
+		let more_data_to_read = self.magic_ring_buffer.single_reader_read_some_data::<DequeuedMessageProcessingError, _>
+		(
+			|buffer|
+			{
+				let (total_message_size, outcome) = MessageRepresentation::process_next_message_in_buffer::<Result<(), DequeuedMessageProcessingError>, _>(buffer, &mut message_processor);
+				bytes_dequeued = total_message_size;
+				(total_message_size, outcome)
+			}
+		);
+		self.queue_occupancy.release(bytes_dequeued);
+		Ok((more_data_to_read?, bytes_dequeued))
+	}
+	
+	/// Drops every message in the magic ring buffer and the overflow list in place.
+	#[inline(always)]
+	fn drop_all_messages_in_place<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>(&self, message_handlers: &MessageHandlers<MessageHandlerArguments, Result<(), DequeuedMessageProcessingError>>)
+	{
+		while
+		{
+			let (more_data_to_read, _bytes_dequeued) = self.process_next_message_in_magic_ring_buffer
+			(
+				|compressed_type_identifier, _message_header_extensions, variably_sized_message_body|
+				{
+					message_handlers.drop_in_place(compressed_type_identifier, variably_sized_message_body);
+					Ok::<(), DequeuedMessageProcessingError>(())
+				}
+			).expect("Should never happen");
+			
+			more_data_to_read
+		}
+		{
+		}
+		
+		let mut overflow = self.overflow.lock().unwrap();
+		self.bytes_spilled.store(0, Relaxed);
+		for mut spilled_message in overflow.drain(..)
+		{
+			spilled_message.process(|compressed_type_identifier, _message_header_extensions, variably_sized_message_body| message_handlers.drop_in_place(compressed_type_identifier, variably_sized_message_body));
+		}
+	}
+	
+	/// Drops the oldest messages in the magic ring buffer, then, if that is not enough, in the overflow list, to make up for those spilled by the `DropOldest` overflow policy.
+	///
+	/// Eviction is deferred until dequeue because only the single consumer may read from the magic ring buffer.
+	#[inline(always)]
+	fn evict_oldest_messages<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>(&self, message_handlers: &MessageHandlers<MessageHandlerArguments, Result<(), DequeuedMessageProcessingError>>, overflow_counters: &OverflowCounters)
+	{
+		let bytes_of_oldest_messages_to_evict = self.bytes_of_oldest_messages_to_evict.swap(0, AcqRel);
+		if bytes_of_oldest_messages_to_evict == 0
+		{
+			return
+		}
+		
+		let mut bytes_evicted = 0;
+		while
+		{
+			let (more_data_to_read, bytes_dequeued) = self.process_next_message_in_magic_ring_buffer
+			(
+				|compressed_type_identifier, _message_header_extensions, variably_sized_message_body|
+				{
+					message_handlers.drop_in_place(compressed_type_identifier, variably_sized_message_body);
+					overflow_counters.increment_dropped_oldest();
+					Ok::<(), DequeuedMessageProcessingError>(())
+				}
+			).expect("Should never happen");
+			bytes_evicted += bytes_dequeued as u64;
+			
+			more_data_to_read && bytes_evicted < bytes_of_oldest_messages_to_evict
+		}
+		{
+		}
+		
+		while bytes_evicted < bytes_of_oldest_messages_to_evict
+		{
+			match self.pop_spilled_message()
+			{
+				None => break,
+				
+				Some(mut spilled_message) =>
+				{
+					spilled_message.process(|compressed_type_identifier, _message_header_extensions, variably_sized_message_body| message_handlers.drop_in_place(compressed_type_identifier, variably_sized_message_body));
+					overflow_counters.increment_dropped_oldest();
+					bytes_evicted += spilled_message.total_message_size() as u64
+				}
+			}
+		}
+	}
+	
+	/// Records that `bytes_needed` of the oldest messages should be evicted at the next dequeue.
+	#[inline(always)]
+	fn evict_oldest_messages_later(&self, bytes_needed: usize)
+	{
+		self.bytes_of_oldest_messages_to_evict.fetch_add(bytes_needed as u64, AcqRel);
+	}
+	
+	#[inline(always)]
+	fn must_spill_to_preserve_ordering(&self, overflow_policy: OverflowPolicy) -> bool
+	{
+		overflow_policy.spills() && self.overflow_is_not_empty.load(Acquire)
+	}
+	
+	/// Appends a message constructed on the heap to `overflow`.
+	#[inline(always)]
+	fn push_spilled_message(&self, spilled_message: SpilledMessage)
+	{
+		let mut overflow = self.overflow.lock().unwrap();
+		self.bytes_spilled.fetch_add(spilled_message.total_message_size() as u64, Relaxed);
+		overflow.push_back(spilled_message);
+		self.overflow_is_not_empty.store(true, SeqCst);
+	}
+	
+	/// Appends a message constructed on the heap to `overflow`, then, whilst `overflow` holds more bytes than the magic ring buffer can, drops its oldest messages in place.
+	///
+	/// Used by the `DropOldest` overflow policy; as the bytes of those dropped messages were to be evicted at the next dequeue anyway, they are no longer.
+	/// The message appended is never dropped, as it can always fit in the magic ring buffer.
+	#[inline(always)]
+	fn push_spilled_message_dropping_oldest<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>(&self, spilled_message: SpilledMessage, message_handlers: &MessageHandlers<MessageHandlerArguments, Result<(), DequeuedMessageProcessingError>>, overflow_counters: &OverflowCounters)
+	{
+		let mut oldest_spilled_messages = Vec::new();
+		
+		{
+			let mut overflow = self.overflow.lock().unwrap();
+			let mut bytes_spilled = self.bytes_spilled.load(Relaxed) + (spilled_message.total_message_size() as u64);
+			overflow.push_back(spilled_message);
+			while bytes_spilled > self.capacity_in_bytes()
+			{
+				let oldest_spilled_message = overflow.pop_front().unwrap();
+				bytes_spilled -= oldest_spilled_message.total_message_size() as u64;
+				oldest_spilled_messages.push(oldest_spilled_message)
+			}
+			self.bytes_spilled.store(bytes_spilled, Relaxed);
+			self.overflow_is_not_empty.store(true, SeqCst);
+		}
+		
+		// Dropped once the lock is released, as a message's drop may itself publish.
+		for mut oldest_spilled_message in oldest_spilled_messages
+		{
+			let total_message_size = oldest_spilled_message.total_message_size() as u64;
+			let _ = self.bytes_of_oldest_messages_to_evict.fetch_update(AcqRel, Acquire, |bytes_of_oldest_messages_to_evict| Some(bytes_of_oldest_messages_to_evict.saturating_sub(total_message_size)));
+			oldest_spilled_message.process(|compressed_type_identifier, _message_header_extensions, variably_sized_message_body| message_handlers.drop_in_place(compressed_type_identifier, variably_sized_message_body));
+			overflow_counters.increment_dropped_oldest()
+		}
+	}
+	
+	#[inline(always)]
+	fn pop_spilled_message(&self) -> Option<SpilledMessage>
+	{
+		if !self.overflow_is_not_empty.load(Acquire)
+		{
+			return None
+		}
+		
+		let mut overflow = self.overflow.lock().unwrap();
+		let spilled_message = overflow.pop_front();
+		if let Some(ref spilled_message) = spilled_message
+		{
+			self.bytes_spilled.fetch_sub(spilled_message.total_message_size() as u64, Relaxed);
+		}
+		if overflow.is_empty()
+		{
+			self.overflow_is_not_empty.store(false, SeqCst)
+		}
+		spilled_message
+	}
+	
+	/// The capacity of the magic ring buffer.
+	#[inline(always)]
+	fn capacity_in_bytes(&self) -> u64
+	{
+		self.queue_occupancy.capacity_in_bytes()
+	}
+	
+	/// The number of bytes in the magic ring buffer, or `u64::MAX` if messages have been spilled to the heap.
+	#[inline(always)]
+	fn load(&self) -> u64
+	{
+		if self.overflow_is_not_empty.load(Relaxed)
+		{
+			u64::MAX
+		}
+		else
+		{
+			self.queue_occupancy.bytes_reserved()
+		}
+	}
+	
+	/// Are there messages in either the magic ring buffer or the overflow list?
+	#[inline(always)]
+	fn has_pending_messages(&self) -> bool
+	{
+		self.queue_occupancy.is_not_empty() || self.overflow_is_not_empty.load(SeqCst)
+	}
+}
@@ -0,0 +1,145 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// Stops lower priority lanes being starved of dequeues by a constant stream of messages in higher priority lanes.
+///
+/// Once `maximum_consecutive_messages_whilst_lower_priority_lanes_wait` messages in a row have been dequeued from higher priority lanes whilst a lower priority lane had pending messages, one message is dequeued from a lower priority lane.
+/// Lower priority lanes take turns, so that no lane other than the highest priority lane is always chosen.
+///
+/// A queue's compact lane, if any, is the highest priority lane, above the first priority lane.
+///
+/// Only ever used by the single consumer of a `Queue`.
+#[derive(Debug)]
+struct PriorityLaneStarvationGuard
+{
+	maximum_consecutive_messages_whilst_lower_priority_lanes_wait: Option<NonZeroU64>,
+	consecutive_messages_whilst_lower_priority_lanes_wait: Cell<u64>,
+	next_starved_priority_lane_index: Cell<usize>,
+}
+
+impl PriorityLaneStarvationGuard
+{
+	#[inline(always)]
+	fn new(maximum_consecutive_messages_whilst_lower_priority_lanes_wait: Option<NonZeroU64>) -> Self
+	{
+		Self
+		{
+			maximum_consecutive_messages_whilst_lower_priority_lanes_wait,
+			consecutive_messages_whilst_lower_priority_lanes_wait: Cell::new(0),
+			next_starved_priority_lane_index: Cell::new(0),
+		}
+	}
+	
+	/// Processes the next message by priority.
+	///
+	/// Returns the number of bytes dequeued if there was a message.
+	#[inline(always)]
+	fn process_next_message<DequeuedMessageProcessingError>(&self, compact_lane: Option<&CompactLane>, priority_lanes: &[PriorityLane], mut message_processor: impl FnMut(CompressedTypeIdentifier, MessageHeaderExtensions, NonNull<VariablySizedMessageBody>) -> Result<(), DequeuedMessageProcessingError>) -> Result<Option<usize>, DequeuedMessageProcessingError>
+	{
+		let first_starvable_priority_lane_index = Self::first_starvable_priority_lane_index(compact_lane);
+		
+		if self.is_tripped()
+		{
+			self.consecutive_messages_whilst_lower_priority_lanes_wait.set(0);
+			if let Some(bytes_dequeued) = self.process_next_message_in_a_starved_priority_lane(first_starvable_priority_lane_index, priority_lanes, &mut message_processor)?
+			{
+				return Ok(Some(bytes_dequeued))
+			}
+		}
+		
+		if let Some(compact_lane) = compact_lane
+		{
+			if let Some(bytes_dequeued) = compact_lane.process_next_message(&mut message_processor)?
+			{
+				self.record_message_dequeued(priority_lanes, 0);
+				return Ok(Some(bytes_dequeued))
+			}
+		}
+		
+		for (priority_lane_index, priority_lane) in priority_lanes.iter().enumerate()
+		{
+			if let Some(bytes_dequeued) = priority_lane.process_next_message(&mut message_processor)?
+			{
+				self.record_message_dequeued(priority_lanes, priority_lane_index + 1);
+				return Ok(Some(bytes_dequeued))
+			}
+		}
+		
+		Ok(None)
+	}
+	
+	#[inline(always)]
+	fn is_tripped(&self) -> bool
+	{
+		match self.maximum_consecutive_messages_whilst_lower_priority_lanes_wait
+		{
+			None => false,
+			
+			Some(maximum_consecutive_messages_whilst_lower_priority_lanes_wait) => self.consecutive_messages_whilst_lower_priority_lanes_wait.get() >= maximum_consecutive_messages_whilst_lower_priority_lanes_wait.get(),
+		}
+	}
+	
+	/// Without a compact lane, the first priority lane is the highest priority lane and so is never starved.
+	#[inline(always)]
+	fn first_starvable_priority_lane_index(compact_lane: Option<&CompactLane>) -> usize
+	{
+		if compact_lane.is_some()
+		{
+			0
+		}
+		else
+		{
+			1
+		}
+	}
+	
+	#[inline(always)]
+	fn record_message_dequeued(&self, priority_lanes: &[PriorityLane], first_lower_priority_lane_index: usize)
+	{
+		if self.maximum_consecutive_messages_whilst_lower_priority_lanes_wait.is_none()
+		{
+			return
+		}
+		
+		let lower_priority_lanes_wait = priority_lanes[first_lower_priority_lane_index .. ].iter().any(PriorityLane::has_pending_messages);
+		if lower_priority_lanes_wait
+		{
+			self.consecutive_messages_whilst_lower_priority_lanes_wait.set(self.consecutive_messages_whilst_lower_priority_lanes_wait.get() + 1)
+		}
+		else
+		{
+			self.consecutive_messages_whilst_lower_priority_lanes_wait.set(0)
+		}
+	}
+	
+	/// Tries each priority lane from `first_starvable_priority_lane_index` in turn, starting after the one last chosen.
+	#[inline(always)]
+	fn process_next_message_in_a_starved_priority_lane<DequeuedMessageProcessingError>(&self, first_starvable_priority_lane_index: usize, priority_lanes: &[PriorityLane], message_processor: &mut impl FnMut(CompressedTypeIdentifier, MessageHeaderExtensions, NonNull<VariablySizedMessageBody>) -> Result<(), DequeuedMessageProcessingError>) -> Result<Option<usize>, DequeuedMessageProcessingError>
+	{
+		let last_priority_lane_index = priority_lanes.len() - 1;
+		
+		let mut priority_lane_index = max(self.next_starved_priority_lane_index.get(), first_starvable_priority_lane_index);
+		for _ in first_starvable_priority_lane_index ..= last_priority_lane_index
+		{
+			let following_priority_lane_index = if priority_lane_index == last_priority_lane_index
+			{
+				first_starvable_priority_lane_index
+			}
+			else
+			{
+				priority_lane_index + 1
+			};
+			
+			if let Some(bytes_dequeued) = priority_lanes.get_unchecked_safe(priority_lane_index).process_next_message(&mut *message_processor)?
+			{
+				self.next_starved_priority_lane_index.set(following_priority_lane_index);
+				return Ok(Some(bytes_dequeued))
+			}
+			
+			priority_lane_index = following_priority_lane_index
+		}
+		
+		Ok(None)
+	}
+}
@@ -0,0 +1,135 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// An error when publishing without waiting for space in a queue.
+///
+/// Unless the message was dead lettered, it was never constructed; the unconsumed `ConstructMessageArguments` are handed back so that they can be rerouted or shed.
+pub enum PublishError<ConstructMessageArguments>
+{
+	/// The queue for `hyper_thread` did not have space for a message of `bytes_needed`.
+	QueueFull
+	{
+		/// The hyper thread whose queue was full.
+		///
+		/// This is the hyper thread actually published to, which may be the default hyper thread.
+		hyper_thread: HyperThread,
+		
+		/// The total number of bytes the message would have occupied in the queue, including its message header and padding.
+		bytes_needed: usize,
+		
+		/// Unconsumed arguments.
+		construct_message_arguments: ConstructMessageArguments,
+	},
+	
+	/// The message could never be enqueued in the queue for `hyper_thread`, as it is too large or too aligned to be represented or is larger than the queue.
+	///
+	/// Publishing the same message by waiting for space in the queue panics instead.
+	TooLarge
+	{
+		/// The hyper thread whose queue the message could never be enqueued in.
+		///
+		/// This is the hyper thread actually published to, which may be the default hyper thread.
+		hyper_thread: HyperThread,
+		
+		/// The total number of bytes the message would have occupied in the queue, including its message header and padding.
+		bytes_needed: usize,
+		
+		/// Unconsumed arguments.
+		construct_message_arguments: ConstructMessageArguments,
+	},
+	
+	/// The message's type is not registered for the queue for `hyper_thread`, and the queue has no `DeadLetterSink`.
+	Unregistered
+	{
+		/// The hyper thread whose queue the message's type is not registered for.
+		///
+		/// This is the hyper thread actually published to, which may be the default hyper thread.
+		hyper_thread: HyperThread,
+		
+		/// Unconsumed arguments.
+		construct_message_arguments: ConstructMessageArguments,
+	},
+	
+	/// The message's type is not registered for the queue for `hyper_thread`; it was constructed and moved to the queue's `DeadLetterSink` with `DeadLetterReason::Unregistered` rather than delivered.
+	DeadLettered
+	{
+		/// The hyper thread whose queue the message's type is not registered for.
+		///
+		/// This is the hyper thread actually published to, which may be the default hyper thread.
+		hyper_thread: HyperThread,
+	},
+}
+
+impl<ConstructMessageArguments> Debug for PublishError<ConstructMessageArguments>
+{
+	#[inline(always)]
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result
+	{
+		use self::PublishError::*;
+		
+		match self
+		{
+			&QueueFull { hyper_thread, bytes_needed, .. } => write!(f, "QueueFull {{ hyper_thread: {:?}, bytes_needed: {:?}, construct_message_arguments: _ }}", hyper_thread, bytes_needed),
+			
+			&TooLarge { hyper_thread, bytes_needed, .. } => write!(f, "TooLarge {{ hyper_thread: {:?}, bytes_needed: {:?}, construct_message_arguments: _ }}", hyper_thread, bytes_needed),
+			
+			&Unregistered { hyper_thread, .. } => write!(f, "Unregistered {{ hyper_thread: {:?}, construct_message_arguments: _ }}", hyper_thread),
+			
+			&DeadLettered { hyper_thread } => write!(f, "DeadLettered {{ hyper_thread: {:?} }}", hyper_thread),
+		}
+	}
+}
+
+impl<ConstructMessageArguments> fmt::Display for PublishError<ConstructMessageArguments>
+{
+	#[inline(always)]
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result
+	{
+		Debug::fmt(self, f)
+	}
+}
+
+impl<ConstructMessageArguments> error::Error for PublishError<ConstructMessageArguments>
+{
+}
+
+impl<ConstructMessageArguments> PublishError<ConstructMessageArguments>
+{
+	/// Recovers the unconsumed arguments.
+	///
+	/// Returns `None` if the message was dead lettered, as the arguments were consumed constructing it.
+	#[inline(always)]
+	pub fn into_construct_message_arguments(self) -> Option<ConstructMessageArguments>
+	{
+		use self::PublishError::*;
+		
+		match self
+		{
+			QueueFull { construct_message_arguments, .. } => Some(construct_message_arguments),
+			
+			TooLarge { construct_message_arguments, .. } => Some(construct_message_arguments),
+			
+			Unregistered { construct_message_arguments, .. } => Some(construct_message_arguments),
+			
+			DeadLettered { .. } => None,
+		}
+	}
+	
+	#[inline(always)]
+	pub(crate) fn map_construct_message_arguments<MappedConstructMessageArguments>(self, map: impl FnOnce(ConstructMessageArguments) -> MappedConstructMessageArguments) -> PublishError<MappedConstructMessageArguments>
+	{
+		use self::PublishError::*;
+		
+		match self
+		{
+			QueueFull { hyper_thread, bytes_needed, construct_message_arguments } => QueueFull { hyper_thread, bytes_needed, construct_message_arguments: map(construct_message_arguments) },
+			
+			TooLarge { hyper_thread, bytes_needed, construct_message_arguments } => TooLarge { hyper_thread, bytes_needed, construct_message_arguments: map(construct_message_arguments) },
+			
+			Unregistered { hyper_thread, construct_message_arguments } => Unregistered { hyper_thread, construct_message_arguments: map(construct_message_arguments) },
+			
+			DeadLettered { hyper_thread } => DeadLettered { hyper_thread },
+		}
+	}
+}
@@ -7,11 +7,13 @@
 /// `queues_mapped` holds a reference to `_queues_drop_reference`, making this a self-referential struct.
 /// As `_queues_drop_reference` is internally an `Arc`, this is ok as the reference is to a stable location in memory, ie one that doesn't move.
 /// This can not be expressed using lifetimes, hence the `*const Queue` below (otherwise the lifetime would be `'self', if such a thing existed).
+///
+/// If `M` is not registered with the `MessageHandlers` of a queue, messages published to that queue are moved to its `DeadLetterSink` with `DeadLetterReason::Unregistered`; if it has no `DeadLetterSink`, `publish()` panics and `try_publish()` returns `PublishError::Unregistered`.
 #[derive(Debug)]
 pub struct Publisher<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>
 {
 	_queues_drop_reference: Queues<MessageHandlerArguments, DequeuedMessageProcessingError>,
-	queues_mapped: PerBitSetAwareData<HyperThread, (*const Queue<MessageHandlerArguments, DequeuedMessageProcessingError>, CompressedTypeIdentifier)>,
+	queues_mapped: PerBitSetAwareData<HyperThread, (*const Queue<MessageHandlerArguments, DequeuedMessageProcessingError>, Option<CompressedTypeIdentifier>)>,
 	default_hyper_thread: HyperThread,
 	marker: PhantomData<M>,
 }
@@ -20,11 +22,23 @@ impl<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, Deque
 {
 	#[inline(always)]
 	fn new(queues: &Queues<MessageHandlerArguments, DequeuedMessageProcessingError>, default_hyper_thread: HyperThread) -> Self
+	{
+		Self::new_with_compressed_type_identifiers(queues, default_hyper_thread, |queue| queue.fixed_sized_message_body_compressed_type_identifier::<M>())
+	}
+	
+	#[inline(always)]
+	fn new_for_message_set_member<Set: MessageSet<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>>(queues: &Queues<MessageHandlerArguments, DequeuedMessageProcessingError>, default_hyper_thread: HyperThread) -> Self where M: MessageSetMember<Set>
+	{
+		Self::new_with_compressed_type_identifiers(queues, default_hyper_thread, |queue| queue.message_handlers().find_message_set_member_compressed_type_identifier::<Set, M>())
+	}
+	
+	#[inline(always)]
+	fn new_with_compressed_type_identifiers(queues: &Queues<MessageHandlerArguments, DequeuedMessageProcessingError>, default_hyper_thread: HyperThread, fixed_sized_message_body_compressed_type_identifier: impl Fn(&Queue<MessageHandlerArguments, DequeuedMessageProcessingError>) -> Option<CompressedTypeIdentifier>) -> Self
 	{
 		Self
 		{
 			_queues_drop_reference: queues.clone(),
-			queues_mapped: queues.0.map_ref(|_hyper_thread, queue| (queue as *const _, queue.fixed_sized_message_body_compressed_type_identifier::<M>())),
+			queues_mapped: queues.0.map_ref(|_hyper_thread, queue| (queue as *const _, fixed_sized_message_body_compressed_type_identifier(queue))),
 			default_hyper_thread,
 			marker: PhantomData,
 		}
@@ -38,10 +52,188 @@ impl<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, Deque
 	/// Returns the actual hyper thread published to.
 	#[inline(always)]
 	pub fn publish(&self, hyper_thread: HyperThread, construct_message_arguments: M::ConstructMessageArguments)-> HyperThread
+	{
+		self.publish_with_priority(hyper_thread, Priority::Highest, construct_message_arguments)
+	}
+	
+	/// A publisher publishes to the priority lane for `priority` of a specific hyper thread.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	/// If the queue has fewer priority lanes than `priority` needs, publishes to its lowest priority lane.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish_with_priority(&self, hyper_thread: HyperThread, priority: Priority, construct_message_arguments: M::ConstructMessageArguments) -> HyperThread
+	{
+		self.publish_with_message_header_extensions(hyper_thread, priority, MessageHeaderExtensions::default(), construct_message_arguments)
+	}
+	
+	/// A publisher publishes to a specific hyper thread a message which is not to be handled before `due_time`.
+	///
+	/// The message is enqueued immediately; if it is dequeued before it is due, it waits in the subscriber's timer wheel, which has a resolution of about a millisecond.
+	/// A blocking subscriber wakes up when the next message waiting is due; a subscriber driven by an eventfd or a `Waker` is not woken when a message becomes due, and so should arm a timer for `DequeueOutcome.next_scheduled_message_due_at`.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish_at(&self, hyper_thread: HyperThread, due_time: MonotonicTime, construct_message_arguments: M::ConstructMessageArguments) -> HyperThread
+	{
+		self.publish_at_with_priority(hyper_thread, Priority::Highest, due_time, construct_message_arguments)
+	}
+	
+	/// As for `publish_at()`, but publishes to the priority lane for `priority`.
+	///
+	/// If the queue has fewer priority lanes than `priority` needs, publishes to its lowest priority lane.
+	#[inline(always)]
+	pub fn publish_at_with_priority(&self, hyper_thread: HyperThread, priority: Priority, due_time: MonotonicTime, construct_message_arguments: M::ConstructMessageArguments) -> HyperThread
+	{
+		self.publish_with_message_header_extensions(hyper_thread, priority, MessageHeaderExtensions::due_at(due_time), construct_message_arguments)
+	}
+	
+	/// A publisher publishes to a specific hyper thread a message which is not to be handled until `delay` has elapsed.
+	///
+	/// See `publish_at()`.
+	#[inline(always)]
+	pub fn publish_after(&self, hyper_thread: HyperThread, delay: Duration, construct_message_arguments: M::ConstructMessageArguments) -> HyperThread
+	{
+		self.publish_at(hyper_thread, MonotonicTime::after(delay), construct_message_arguments)
+	}
+	
+	/// A publisher publishes to a specific hyper thread a message which is worthless once `time_to_live` has elapsed.
+	///
+	/// If the message is dequeued after then, its message handler is not called; instead, the expired message handler registered with `MessageHandlers::register_expired_message_handler()`, if any, is called and the message is dropped in place.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish_with_time_to_live(&self, hyper_thread: HyperThread, time_to_live: Duration, construct_message_arguments: M::ConstructMessageArguments) -> HyperThread
+	{
+		self.publish_with_time_to_live_and_priority(hyper_thread, Priority::Highest, time_to_live, construct_message_arguments)
+	}
+	
+	/// As for `publish_with_time_to_live()`, but publishes to the priority lane for `priority`.
+	///
+	/// If the queue has fewer priority lanes than `priority` needs, publishes to its lowest priority lane.
+	#[inline(always)]
+	pub fn publish_with_time_to_live_and_priority(&self, hyper_thread: HyperThread, priority: Priority, time_to_live: Duration, construct_message_arguments: M::ConstructMessageArguments) -> HyperThread
+	{
+		self.publish_with_message_header_extensions(hyper_thread, priority, MessageHeaderExtensions::expires_at(MonotonicTime::after(time_to_live)), construct_message_arguments)
+	}
+	
+	/// A publisher publishes to the priority lane for `priority` of a specific hyper thread a message which is not to be handled before `due_time`, and which is worthless once `time_to_live` has elapsed after `due_time`.
+	///
+	/// See `publish_at()` and `publish_with_time_to_live()`.
+	#[inline(always)]
+	pub fn publish_at_with_time_to_live_and_priority(&self, hyper_thread: HyperThread, priority: Priority, due_time: MonotonicTime, time_to_live: Duration, construct_message_arguments: M::ConstructMessageArguments) -> HyperThread
+	{
+		self.publish_with_message_header_extensions(hyper_thread, priority, MessageHeaderExtensions::due_at_and_expires_at(due_time, due_time.saturating_add(time_to_live)), construct_message_arguments)
+	}
+	
+	#[inline(always)]
+	fn publish_with_message_header_extensions(&self, hyper_thread: HyperThread, priority: Priority, message_header_extensions: MessageHeaderExtensions, construct_message_arguments: M::ConstructMessageArguments) -> HyperThread
 	{
 		let (&(queue, fixed_sized_message_body_compressed_type_identifier), actual_hyper_thread) = self.queues_mapped.get_or(hyper_thread, self.default_hyper_thread);
 		
-		unsafe { (& * queue).enqueue(fixed_sized_message_body_compressed_type_identifier, |uninitialized_memory| M::construct_message(uninitialized_memory, construct_message_arguments)) };
+		Self::publish_to_queue(unsafe { & * queue }, actual_hyper_thread, fixed_sized_message_body_compressed_type_identifier, priority, message_header_extensions, construct_message_arguments)
+	}
+	
+	/// Also used by `Queues::publish_safe_but_slow()`.
+	#[inline(always)]
+	fn publish_to_queue(queue: &Queue<MessageHandlerArguments, DequeuedMessageProcessingError>, actual_hyper_thread: HyperThread, fixed_sized_message_body_compressed_type_identifier: Option<CompressedTypeIdentifier>, priority: Priority, message_header_extensions: MessageHeaderExtensions, construct_message_arguments: M::ConstructMessageArguments) -> HyperThread
+	{
+		unsafe { queue.enqueue_or_dead_letter(fixed_sized_message_body_compressed_type_identifier, priority, message_header_extensions, 0, |uninitialized_memory| M::construct_message(uninitialized_memory, construct_message_arguments)) };
+		actual_hyper_thread
+	}
+	
+	/// A publisher publishes an already constructed `message` to a specific hyper thread, moving it into the queue.
+	///
+	/// Unlike `publish()`, `M::construct_message()` is not called.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish_value(&self, hyper_thread: HyperThread, message: M) -> HyperThread
+	{
+		self.publish_value_with_priority(hyper_thread, Priority::Highest, message)
+	}
+	
+	/// As for `publish_value()`, but publishes to the priority lane for `priority`.
+	///
+	/// If the queue has fewer priority lanes than `priority` needs, publishes to its lowest priority lane.
+	#[inline(always)]
+	pub fn publish_value_with_priority(&self, hyper_thread: HyperThread, priority: Priority, message: M) -> HyperThread
+	{
+		let (&(queue, fixed_sized_message_body_compressed_type_identifier), actual_hyper_thread) = self.queues_mapped.get_or(hyper_thread, self.default_hyper_thread);
+		
+		unsafe { (& * queue).enqueue_or_dead_letter(fixed_sized_message_body_compressed_type_identifier, priority, MessageHeaderExtensions::default(), 0, |uninitialized_memory| write(uninitialized_memory.as_ptr(), message)) };
 		actual_hyper_thread
 	}
+	
+	/// A publisher publishes to a specific hyper thread without waiting for space in the queue.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	///
+	/// Returns the actual hyper thread published to, or, if its queue is full, a `PublishError::QueueFull` which hands back the unconsumed `construct_message_arguments`.
+	#[inline(always)]
+	pub fn try_publish(&self, hyper_thread: HyperThread, construct_message_arguments: M::ConstructMessageArguments) -> Result<HyperThread, PublishError<M::ConstructMessageArguments>>
+	{
+		self.try_publish_with_priority(hyper_thread, Priority::Highest, construct_message_arguments)
+	}
+	
+	/// A publisher publishes to the priority lane for `priority` of a specific hyper thread without waiting for space in the queue.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	/// If the queue has fewer priority lanes than `priority` needs, publishes to its lowest priority lane.
+	///
+	/// Returns the actual hyper thread published to, or, if its priority lane is full, a `PublishError::QueueFull` which hands back the unconsumed `construct_message_arguments`.
+	#[inline(always)]
+	pub fn try_publish_with_priority(&self, hyper_thread: HyperThread, priority: Priority, construct_message_arguments: M::ConstructMessageArguments) -> Result<HyperThread, PublishError<M::ConstructMessageArguments>>
+	{
+		self.try_publish_with_message_header_extensions(hyper_thread, priority, MessageHeaderExtensions::default(), construct_message_arguments)
+	}
+	
+	/// As for `publish_at_with_priority()`, but without waiting for space in the queue.
+	///
+	/// Returns the actual hyper thread published to, or, if its priority lane is full, a `PublishError::QueueFull` which hands back the unconsumed `construct_message_arguments`.
+	#[inline(always)]
+	pub fn try_publish_at_with_priority(&self, hyper_thread: HyperThread, priority: Priority, due_time: MonotonicTime, construct_message_arguments: M::ConstructMessageArguments) -> Result<HyperThread, PublishError<M::ConstructMessageArguments>>
+	{
+		self.try_publish_with_message_header_extensions(hyper_thread, priority, MessageHeaderExtensions::due_at(due_time), construct_message_arguments)
+	}
+	
+	/// As for `publish_with_time_to_live_and_priority()`, but without waiting for space in the queue.
+	///
+	/// Returns the actual hyper thread published to, or, if its priority lane is full, a `PublishError::QueueFull` which hands back the unconsumed `construct_message_arguments`.
+	#[inline(always)]
+	pub fn try_publish_with_time_to_live_and_priority(&self, hyper_thread: HyperThread, priority: Priority, time_to_live: Duration, construct_message_arguments: M::ConstructMessageArguments) -> Result<HyperThread, PublishError<M::ConstructMessageArguments>>
+	{
+		self.try_publish_with_message_header_extensions(hyper_thread, priority, MessageHeaderExtensions::expires_at(MonotonicTime::after(time_to_live)), construct_message_arguments)
+	}
+	
+	/// As for `publish_at_with_time_to_live_and_priority()`, but without waiting for space in the queue.
+	///
+	/// Returns the actual hyper thread published to, or, if its priority lane is full, a `PublishError::QueueFull` which hands back the unconsumed `construct_message_arguments`.
+	#[inline(always)]
+	pub fn try_publish_at_with_time_to_live_and_priority(&self, hyper_thread: HyperThread, priority: Priority, due_time: MonotonicTime, time_to_live: Duration, construct_message_arguments: M::ConstructMessageArguments) -> Result<HyperThread, PublishError<M::ConstructMessageArguments>>
+	{
+		self.try_publish_with_message_header_extensions(hyper_thread, priority, MessageHeaderExtensions::due_at_and_expires_at(due_time, due_time.saturating_add(time_to_live)), construct_message_arguments)
+	}
+	
+	#[inline(always)]
+	fn try_publish_with_message_header_extensions(&self, hyper_thread: HyperThread, priority: Priority, message_header_extensions: MessageHeaderExtensions, construct_message_arguments: M::ConstructMessageArguments) -> Result<HyperThread, PublishError<M::ConstructMessageArguments>>
+	{
+		let (&(queue, fixed_sized_message_body_compressed_type_identifier), actual_hyper_thread) = self.queues_mapped.get_or(hyper_thread, self.default_hyper_thread);
+		
+		Self::try_publish_to_queue(unsafe { & * queue }, actual_hyper_thread, fixed_sized_message_body_compressed_type_identifier, priority, message_header_extensions, construct_message_arguments)
+	}
+	
+	/// Also used by `Queues::try_publish_safe_but_slow()`.
+	#[inline(always)]
+	fn try_publish_to_queue(queue: &Queue<MessageHandlerArguments, DequeuedMessageProcessingError>, actual_hyper_thread: HyperThread, fixed_sized_message_body_compressed_type_identifier: Option<CompressedTypeIdentifier>, priority: Priority, message_header_extensions: MessageHeaderExtensions, construct_message_arguments: M::ConstructMessageArguments) -> Result<HyperThread, PublishError<M::ConstructMessageArguments>>
+	{
+		unsafe { queue.try_enqueue_or_dead_letter(actual_hyper_thread, fixed_sized_message_body_compressed_type_identifier, priority, message_header_extensions, 0, construct_message_arguments, |uninitialized_memory, construct_message_arguments| M::construct_message(uninitialized_memory, construct_message_arguments)) }
+	}
 }
@@ -10,10 +10,34 @@
 /// `DequeuedMessageProcessingError` must be common to all possible message types (all possible `FixedSizeMessageBody` and `CompressedTypeIdentifier`s).
 ///
 /// Both a sending thread and the receiving thread have to agree on `message_handlers` so that `drop()` can work.
+///
+/// Messages are enqueued in one of one or more `priority_lanes`, each with its own magic ring buffer; the first priority lane has the highest priority.
+/// Messages in higher priority lanes are dequeued before those in lower priority lanes, subject to the `priority_lane_starvation_guard`.
+///
+/// When a priority lane's magic ring buffer is full, `overflow_policy` decides what happens to a message being enqueued.
+/// Messages spilled to the heap are dequeued after those in the priority lane's magic ring buffer; whilst any are waiting, all newly enqueued messages for the priority lane are spilled to preserve first-in, first-out ordering.
+///
+/// The optional `compact_lane` is a priority lane above the first priority lane, with its own overflow list; it holds only compact messages.
+///
+/// A message with a due time which is dequeued before it is due is moved to the `timer_wheel`, and is handled once it is due before any other messages.
+///
+/// A message with an expiry time which is dequeued at or after it has expired is not handled; instead, its expired message handler, if any, is called and it is dropped in place.
 #[derive(Debug)]
 struct Queue<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>
 {
-	magic_ring_buffer: MagicRingBuffer,
+	priority_lanes: Box<[PriorityLane]>,
+	priority_lane_starvation_guard: PriorityLaneStarvationGuard,
+	overflow_policy: OverflowPolicy,
+	overflow_counters: OverflowCounters,
+	messages_expired: AtomicU64,
+	catch_message_handler_panics: bool,
+	message_handler_panics: RefCell<Vec<HandlerPanicked>>,
+	subscriber_thread: AtomicU64,
+	subscriber_wake_up: SubscriberWakeUp,
+	subscriber_waker: SubscriberWaker,
+	event_file_descriptor: Option<QueueEventFileDescriptor>,
+	compact_lane: Option<CompactLane>,
+	timer_wheel: RefCell<TimerWheel>,
 	message_handlers: MessageHandlers<MessageHandlerArguments, Result<(), DequeuedMessageProcessingError>>,
 }
 
@@ -23,27 +47,20 @@ impl<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> Drop
 	fn drop(&mut self)
 	{
 		let message_handlers = self.message_handlers();
-		while
+		
+		for mut scheduled_message in self.timer_wheel.borrow_mut().drain()
 		{
-			let more_data_to_read = self.magic_ring_buffer.single_reader_read_some_data::<DequeuedMessageProcessingError, _>
-			(
-				|buffer|
-				{
-					MessageRepresentation::process_next_message_in_buffer::<Result<(), DequeuedMessageProcessingError>, _>
-					(
-						buffer,
-						|compressed_type_identifier, variably_sized_message_body|
-						{
-							message_handlers.drop_in_place(compressed_type_identifier, variably_sized_message_body);
-							Ok(())
-						}
-					)
-				}
-			).expect("Should never happen");
-
-			more_data_to_read
+			scheduled_message.process(|compressed_type_identifier, variably_sized_message_body| message_handlers.drop_in_place(compressed_type_identifier, variably_sized_message_body))
 		}
+		
+		if let Some(ref compact_lane) = self.compact_lane
 		{
+			compact_lane.drop_all_messages_in_place(message_handlers)
+		}
+		
+		for priority_lane in self.priority_lanes.iter()
+		{
+			priority_lane.drop_all_messages_in_place(message_handlers)
 		}
 	}
 }
@@ -51,70 +68,805 @@ impl<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> Drop
 impl<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> Enqueue for Queue<MessageHandlerArguments, DequeuedMessageProcessingError>
 {
 	#[inline(always)]
-	fn fixed_sized_message_body_compressed_type_identifier<FixedSizeMessageBody: 'static + Sized>(&self) -> CompressedTypeIdentifier
+	fn fixed_sized_message_body_compressed_type_identifier<FixedSizeMessageBody: 'static + Sized>(&self) -> Option<CompressedTypeIdentifier>
+	{
+		self.message_handlers.find_fixed_size_message_body_compressed_type_identifier::<FixedSizeMessageBody>()
+	}
+	
+	#[inline(always)]
+	unsafe fn enqueue_with_trailing_bytes<FixedSizeMessageBody: Sized>(&self, fixed_sized_message_body_compressed_type_identifier: CompressedTypeIdentifier, priority: Priority, message_header_extensions: MessageHeaderExtensions, trailing_bytes: usize, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>))
 	{
-		self.message_handlers.find_fixed_size_message_body_compressed_type_identifier::<FixedSizeMessageBody>().expect("Unregistered FixedSizeMessageBody")
+		let priority_lane = self.priority_lane(priority);
+		let bytes_needed = self.bytes_needed::<FixedSizeMessageBody>(priority_lane, message_header_extensions, trailing_bytes);
+		
+		use self::OverflowPolicy::*;
+		
+		if priority_lane.must_spill_to_preserve_ordering(self.overflow_policy)
+		{
+			// Whilst messages are spilled, the priority lane holds more than its magic ring buffer can; it is still full.
+			if self.overflow_policy == DropOldest
+			{
+				return self.spill_dropping_oldest(priority_lane, bytes_needed, fixed_sized_message_body_compressed_type_identifier, message_header_extensions, fixed_size_message_body_constructor)
+			}
+			return self.spill(priority_lane, bytes_needed, fixed_sized_message_body_compressed_type_identifier, message_header_extensions, fixed_size_message_body_constructor)
+		}
+		
+		if let Some(previous_bytes_reserved) = priority_lane.queue_occupancy.try_reserve(bytes_needed)
+		{
+			return self.enqueue_in_magic_ring_buffer(priority_lane, previous_bytes_reserved, bytes_needed, fixed_sized_message_body_compressed_type_identifier, message_header_extensions, fixed_size_message_body_constructor)
+		}
+		
+		match self.overflow_policy
+		{
+			Block { maximum_spins_before_yielding } =>
+			{
+				let previous_bytes_reserved = self.block_until_reserved(maximum_spins_before_yielding, || priority_lane.queue_occupancy.try_reserve(bytes_needed));
+				self.enqueue_in_magic_ring_buffer(priority_lane, previous_bytes_reserved, bytes_needed, fixed_sized_message_body_compressed_type_identifier, message_header_extensions, fixed_size_message_body_constructor)
+			}
+			
+			DropNewest => self.overflow_counters.increment_dropped_newest(),
+			
+			DropOldest => self.spill_dropping_oldest(priority_lane, bytes_needed, fixed_sized_message_body_compressed_type_identifier, message_header_extensions, fixed_size_message_body_constructor),
+			
+			SpillToHeap => self.spill(priority_lane, bytes_needed, fixed_sized_message_body_compressed_type_identifier, message_header_extensions, fixed_size_message_body_constructor),
+		}
 	}
 	
 	#[inline(always)]
-	unsafe fn enqueue<FixedSizeMessageBody: Sized>(&self, fixed_sized_message_body_compressed_type_identifier: CompressedTypeIdentifier, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>))
+	unsafe fn try_enqueue_with_trailing_bytes<FixedSizeMessageBody: Sized, ConstructMessageArguments>(&self, fixed_sized_message_body_compressed_type_identifier: CompressedTypeIdentifier, priority: Priority, message_header_extensions: MessageHeaderExtensions, trailing_bytes: usize, construct_message_arguments: ConstructMessageArguments, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>, ConstructMessageArguments)) -> Result<(), TryEnqueueError<ConstructMessageArguments>>
 	{
-		MessageRepresentation::enqueue(&self.magic_ring_buffer, fixed_sized_message_body_compressed_type_identifier, fixed_size_message_body_constructor)
+		let priority_lane = self.priority_lane(priority);
+		let bytes_needed = match self.bytes_needed_if_could_ever_fit::<FixedSizeMessageBody>(priority_lane, message_header_extensions, trailing_bytes)
+		{
+			Ok(bytes_needed) => bytes_needed,
+			
+			Err(bytes_needed) => return Err(TryEnqueueError::TooLarge { bytes_needed, construct_message_arguments }),
+		};
+		if priority_lane.must_spill_to_preserve_ordering(self.overflow_policy)
+		{
+			return Err(TryEnqueueError::QueueFull { bytes_needed, construct_message_arguments })
+		}
+		
+		match priority_lane.queue_occupancy.try_reserve(bytes_needed)
+		{
+			Some(previous_bytes_reserved) =>
+			{
+				self.enqueue_in_magic_ring_buffer(priority_lane, previous_bytes_reserved, bytes_needed, fixed_sized_message_body_compressed_type_identifier, message_header_extensions, |uninitialized_memory| fixed_size_message_body_constructor(uninitialized_memory, construct_message_arguments));
+				Ok(())
+			}
+			
+			None => Err(TryEnqueueError::QueueFull { bytes_needed, construct_message_arguments }),
+		}
 	}
 }
 
 impl<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> Dequeue<MessageHandlerArguments, DequeuedMessageProcessingError> for Queue<MessageHandlerArguments, DequeuedMessageProcessingError>
 {
 	/// Dequeues messages.
+	///
+	/// If the queue has an eventfd, it is acknowledged before dequeuing and signalled again afterwards if messages are still pending (eg because `terminate` or `dequeue_budget` stopped dequeuing early).
+	///
+	/// The time at which the next message in the timer wheel is due, if any, is reported in `DequeueOutcome.next_scheduled_message_due_at`.
 	#[inline(always)]
-	fn dequeue(&self, terminate: &Arc<impl Terminate>, message_handler_arguments: &MessageHandlerArguments) -> Result<(), DequeuedMessageProcessingError>
+	fn dequeue(&self, terminate: &Arc<impl Terminate>, message_handler_arguments: &MessageHandlerArguments, dequeue_budget: &DequeueBudget, dequeued_message_processing_error_policy: &mut DequeuedMessageProcessingErrorPolicy<DequeuedMessageProcessingError>) -> Result<DequeueOutcome, DequeueFailed<DequeuedMessageProcessingError>>
 	{
-		let message_handlers = self.message_handlers();
-		while
+		self.acknowledge_event_file_descriptor();
+		let mut dequeue_outcome = DequeueOutcome::default();
+		let result = self.dequeue_from_timer_wheel_then_lanes(terminate, message_handler_arguments, dequeue_budget, dequeued_message_processing_error_policy, &mut dequeue_outcome);
+		dequeue_outcome.next_scheduled_message_due_at = self.next_scheduled_message_due_at();
+		self.signal_event_file_descriptor_if_messages_are_still_pending();
+		
+		match result
 		{
-			let more_data_to_read = self.magic_ring_buffer.single_reader_read_some_data::<DequeuedMessageProcessingError, _>
+			Ok(stopped_because) => Ok(dequeue_outcome.stopped_because(stopped_because)),
+			
+			Err(dequeued_message_processing_error) => Err
 			(
-				|buffer|
+				DequeueFailed
 				{
-					MessageRepresentation::process_next_message_in_buffer::<Result<(), DequeuedMessageProcessingError>, _>
-					(
-						buffer,
-						|compressed_type_identifier, variably_sized_message_body|
-						{
-							message_handlers.call_and_drop_in_place(compressed_type_identifier, variably_sized_message_body, message_handler_arguments.clone())
-						}
-					)
+					dequeued_message_processing_error,
+					dequeue_outcome: dequeue_outcome.stopped_because(DequeueStoppedBecause::MessageHandlerFailed),
 				}
-			)?;
-
-			more_data_to_read && terminate.should_continue()
-		}
-		{
+			),
 		}
-
-		Ok(())
 	}
 }
 
 impl<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> Queue<MessageHandlerArguments, DequeuedMessageProcessingError>
 {
+	/// The `load()` of a full queue.
+	const FullLoad: u64 = 1 << 32;
+	
 	/// Allocates a new `Queue`.
+	///
+	/// Each priority lane has its own magic ring buffer of `queue_size_in_bytes`.
+	///
+	/// There is a compact lane only if `queue_settings` asks for one and a compact message handler is registered.
 	#[inline(always)]
-	pub(crate) fn new(message_handlers: MessageHandlers<MessageHandlerArguments, Result<(), DequeuedMessageProcessingError>>, defaults: &DefaultHugePageSizes, queue_size_in_bytes: NonZeroU64, inclusive_maximum_bytes_wasted: u64) -> Result<Self, MirroredMemoryMapCreationError>
+	pub(crate) fn new(message_handlers: MessageHandlers<MessageHandlerArguments, Result<(), DequeuedMessageProcessingError>>, defaults: &DefaultHugePageSizes, queue_size_in_bytes: NonZeroU64, inclusive_maximum_bytes_wasted: u64, queue_settings: &QueueSettings) -> Result<Self, QueueCreationError>
 	{
 		Ok
 		(
 			Self
 			{
-				magic_ring_buffer: MagicRingBuffer::allocate(defaults, queue_size_in_bytes, inclusive_maximum_bytes_wasted)?,
+				priority_lanes:
+				{
+					let number_of_priority_lanes = queue_settings.number_of_priority_lanes.get() as usize;
+					let mut priority_lanes = Vec::with_capacity(number_of_priority_lanes);
+					for _ in 0 .. number_of_priority_lanes
+					{
+						priority_lanes.push(PriorityLane::new(defaults, queue_size_in_bytes, inclusive_maximum_bytes_wasted)?)
+					}
+					priority_lanes.into_boxed_slice()
+				},
+				priority_lane_starvation_guard: PriorityLaneStarvationGuard::new(queue_settings.priority_lane_starvation_guard),
+				overflow_policy: queue_settings.overflow_policy,
+				overflow_counters: OverflowCounters::default(),
+				messages_expired: AtomicU64::new(0),
+				catch_message_handler_panics: queue_settings.catch_message_handler_panics,
+				message_handler_panics: RefCell::new(Vec::new()),
+				subscriber_thread: AtomicU64::new(0),
+				subscriber_wake_up: SubscriberWakeUp::default(),
+				subscriber_waker: SubscriberWaker::default(),
+				event_file_descriptor: if queue_settings.event_file_descriptor
+				{
+					Some(QueueEventFileDescriptor::new().map_err(QueueCreationError::EventFileDescriptorCreation)?)
+				}
+				else
+				{
+					None
+				},
+				timer_wheel: RefCell::new(TimerWheel::default()),
+				compact_lane: match (queue_settings.compact_lane_maximum_number_of_messages, message_handlers.compact_message_compressed_type_identifier())
+				{
+					(Some(maximum_number_of_messages), Some(compressed_type_identifier)) => Some(CompactLane::new(compressed_type_identifier, defaults, maximum_number_of_messages, inclusive_maximum_bytes_wasted)?),
+					
+					_ => None,
+				},
 				message_handlers,
 			}
 		)
 	}
 	
+	/// Enqueues a message in the priority lane for `priority`, or, if its type is not registered (`fixed_sized_message_body_compressed_type_identifier` is `None`), moves it to the dead letter sink with `DeadLetterReason::Unregistered`.
+	///
+	/// Panics if its type is not registered and there is no dead letter sink.
+	#[inline(always)]
+	unsafe fn enqueue_or_dead_letter<FixedSizeMessageBody: 'static + Sized>(&self, fixed_sized_message_body_compressed_type_identifier: Option<CompressedTypeIdentifier>, priority: Priority, message_header_extensions: MessageHeaderExtensions, trailing_bytes: usize, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>))
+	{
+		match fixed_sized_message_body_compressed_type_identifier
+		{
+			Some(fixed_sized_message_body_compressed_type_identifier) => self.enqueue_with_trailing_bytes(fixed_sized_message_body_compressed_type_identifier, priority, message_header_extensions, trailing_bytes, fixed_size_message_body_constructor),
+			
+			None => if self.message_handlers().dead_letter_unregistered(trailing_bytes, (), |uninitialized_memory, ()| fixed_size_message_body_constructor(uninitialized_memory)).is_err()
+			{
+				panic!("Unregistered FixedSizeMessageBody `{}` and no DeadLetterSink", type_name::<FixedSizeMessageBody>())
+			},
+		}
+	}
+	
+	/// As for `enqueue_or_dead_letter()`, but without waiting for space in the queue.
+	///
+	/// Returns `actual_hyper_thread`, or a `PublishError` which hands back `construct_message_arguments` unless the message was moved to the dead letter sink.
+	#[inline(always)]
+	unsafe fn try_enqueue_or_dead_letter<FixedSizeMessageBody: 'static + Sized, ConstructMessageArguments>(&self, actual_hyper_thread: HyperThread, fixed_sized_message_body_compressed_type_identifier: Option<CompressedTypeIdentifier>, priority: Priority, message_header_extensions: MessageHeaderExtensions, trailing_bytes: usize, construct_message_arguments: ConstructMessageArguments, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>, ConstructMessageArguments)) -> Result<HyperThread, PublishError<ConstructMessageArguments>>
+	{
+		let fixed_sized_message_body_compressed_type_identifier = match fixed_sized_message_body_compressed_type_identifier
+		{
+			Some(fixed_sized_message_body_compressed_type_identifier) => fixed_sized_message_body_compressed_type_identifier,
+			
+			None => return match self.message_handlers().dead_letter_unregistered(trailing_bytes, construct_message_arguments, fixed_size_message_body_constructor)
+			{
+				Ok(()) => Err(PublishError::DeadLettered { hyper_thread: actual_hyper_thread }),
+				
+				Err(construct_message_arguments) => Err(PublishError::Unregistered { hyper_thread: actual_hyper_thread, construct_message_arguments }),
+			},
+		};
+		
+		match self.try_enqueue_with_trailing_bytes(fixed_sized_message_body_compressed_type_identifier, priority, message_header_extensions, trailing_bytes, construct_message_arguments, fixed_size_message_body_constructor)
+		{
+			Ok(()) => Ok(actual_hyper_thread),
+			
+			Err(try_enqueue_error) => Err(try_enqueue_error.into_publish_error(actual_hyper_thread)),
+		}
+	}
+	
+	/// Enqueues a `Box<T>` with an opcode in the compact lane as a single `TaggedNonNullPointer`, applying the `overflow_policy` if it is full.
+	///
+	/// If there is no compact lane for `CompactMessage<T>`, or `priority` is not `Priority::Highest`, it is enqueued as an ordinary message in the priority lane for `priority` instead; if it is not registered (`compressed_type_identifier` is `None`), it is moved to the dead letter sink as for `enqueue_or_dead_letter()`.
+	#[inline(always)]
+	unsafe fn enqueue_compact<T: 'static>(&self, compressed_type_identifier: Option<CompressedTypeIdentifier>, priority: Priority, boxed: Box<T>, opcode: u32)
+	{
+		match self.compact_lane_for(compressed_type_identifier, priority)
+		{
+			None => self.enqueue_or_dead_letter(compressed_type_identifier, priority, MessageHeaderExtensions::default(), 0, |uninitialized_memory| write(uninitialized_memory.as_ptr(), CompactMessage::new(boxed, opcode))),
+			
+			Some(compact_lane) => self.enqueue_in_compact_lane(compact_lane, boxed, opcode),
+		}
+	}
+	
+	/// As for `enqueue_compact()`, but without waiting for space in the queue.
+	#[inline(always)]
+	unsafe fn try_enqueue_compact<T: 'static>(&self, actual_hyper_thread: HyperThread, compressed_type_identifier: Option<CompressedTypeIdentifier>, priority: Priority, boxed: Box<T>, opcode: u32) -> Result<HyperThread, PublishError<Box<T>>>
+	{
+		let compact_lane = match self.compact_lane_for(compressed_type_identifier, priority)
+		{
+			None => return self.try_enqueue_or_dead_letter(actual_hyper_thread, compressed_type_identifier, priority, MessageHeaderExtensions::default(), 0, boxed, |uninitialized_memory, boxed| write(uninitialized_memory.as_ptr(), CompactMessage::new(boxed, opcode))),
+			
+			Some(compact_lane) => compact_lane,
+		};
+		
+		if compact_lane.must_spill_to_preserve_ordering(self.overflow_policy)
+		{
+			return Err(PublishError::QueueFull { hyper_thread: actual_hyper_thread, bytes_needed: CompactLane::WordSize, construct_message_arguments: boxed })
+		}
+		
+		match compact_lane.try_reserve()
+		{
+			Some(previous_bytes_reserved) =>
+			{
+				self.write_in_compact_lane(compact_lane, previous_bytes_reserved, boxed, opcode);
+				Ok(actual_hyper_thread)
+			}
+			
+			None => Err(PublishError::QueueFull { hyper_thread: actual_hyper_thread, bytes_needed: CompactLane::WordSize, construct_message_arguments: boxed }),
+		}
+	}
+	
+	/// The compact lane, if there is one, `compressed_type_identifier` is that of its `CompactMessage<T>` and `priority` is `Priority::Highest`.
+	#[inline(always)]
+	fn compact_lane_for(&self, compressed_type_identifier: Option<CompressedTypeIdentifier>, priority: Priority) -> Option<&CompactLane>
+	{
+		match self.compact_lane
+		{
+			Some(ref compact_lane) if priority == Priority::Highest && compact_lane.is_for(compressed_type_identifier) => Some(compact_lane),
+			
+			_ => None,
+		}
+	}
+	
+	/// As for `enqueue_with_trailing_bytes()`, but for the compact lane.
+	#[inline(always)]
+	fn enqueue_in_compact_lane<T: 'static>(&self, compact_lane: &CompactLane, boxed: Box<T>, opcode: u32)
+	{
+		use self::OverflowPolicy::*;
+		
+		if compact_lane.must_spill_to_preserve_ordering(self.overflow_policy)
+		{
+			if self.overflow_policy == DropOldest
+			{
+				return self.spill_in_compact_lane_dropping_oldest(compact_lane, boxed, opcode)
+			}
+			return self.spill_in_compact_lane(compact_lane, boxed, opcode)
+		}
+		
+		if let Some(previous_bytes_reserved) = compact_lane.try_reserve()
+		{
+			return self.write_in_compact_lane(compact_lane, previous_bytes_reserved, boxed, opcode)
+		}
+		
+		match self.overflow_policy
+		{
+			Block { maximum_spins_before_yielding } =>
+			{
+				let previous_bytes_reserved = self.block_until_reserved(maximum_spins_before_yielding, || compact_lane.try_reserve());
+				self.write_in_compact_lane(compact_lane, previous_bytes_reserved, boxed, opcode)
+			}
+			
+			DropNewest =>
+			{
+				self.overflow_counters.increment_dropped_newest();
+				drop(boxed)
+			}
+			
+			DropOldest => self.spill_in_compact_lane_dropping_oldest(compact_lane, boxed, opcode),
+			
+			SpillToHeap => self.spill_in_compact_lane(compact_lane, boxed, opcode),
+		}
+	}
+	
+	/// Writes a message to the compact lane's magic ring buffer once space has been reserved for it.
+	///
+	/// Wakes the subscriber if this message made the compact lane non-empty.
+	#[inline(always)]
+	fn write_in_compact_lane<T: 'static>(&self, compact_lane: &CompactLane, previous_bytes_reserved: u64, boxed: Box<T>, opcode: u32)
+	{
+		compact_lane.write(CompactMessage::tag(boxed, opcode));
+		
+		let compact_lane_was_empty = previous_bytes_reserved == 0;
+		if compact_lane_was_empty
+		{
+			self.queue_became_non_empty()
+		}
+	}
+	
+	/// Appends a message to the compact lane's overflow list.
+	#[inline(always)]
+	fn spill_in_compact_lane<T: 'static>(&self, compact_lane: &CompactLane, boxed: Box<T>, opcode: u32)
+	{
+		compact_lane.push_spilled_message(CompactMessage::tag(boxed, opcode));
+		
+		self.overflow_counters.increment_spilled_to_heap();
+		self.queue_became_non_empty()
+	}
+	
+	/// As for `spill_in_compact_lane()`, but applies the `DropOldest` overflow policy as for `spill_dropping_oldest()`.
+	#[inline(always)]
+	fn spill_in_compact_lane_dropping_oldest<T: 'static>(&self, compact_lane: &CompactLane, boxed: Box<T>, opcode: u32)
+	{
+		compact_lane.evict_oldest_message_later();
+		compact_lane.push_spilled_message_dropping_oldest(CompactMessage::tag(boxed, opcode), self.message_handlers(), self.overflow_counters());
+		
+		self.overflow_counters.increment_spilled_to_heap();
+		self.queue_became_non_empty()
+	}
+	
+	/// Applies the `Block` overflow policy, spinning then yielding until `try_reserve` succeeds.
+	///
+	/// Returns the number of bytes previously reserved.
+	#[inline(always)]
+	fn block_until_reserved(&self, maximum_spins_before_yielding: u32, try_reserve: impl Fn() -> Option<u64>) -> u64
+	{
+		assert!(!self.is_subscriber_thread(), "Publishing to a full queue from its own subscriber's thread would wait forever");
+		self.overflow_counters.increment_blocked();
+		
+		let mut spins = 0;
+		loop
+		{
+			if let Some(previous_bytes_reserved) = try_reserve()
+			{
+				return previous_bytes_reserved
+			}
+			
+			if spins == maximum_spins_before_yielding
+			{
+				yield_now();
+				spins = 0
+			}
+			else
+			{
+				spin_loop();
+				spins += 1
+			}
+		}
+	}
+	
+	/// If there are fewer priority lanes than `priority` needs, the lowest priority lane.
+	#[inline(always)]
+	fn priority_lane(&self, priority: Priority) -> &PriorityLane
+	{
+		self.priority_lanes.get_unchecked_safe(priority.priority_lane_index(self.priority_lanes.len()))
+	}
+	
 	#[inline(always)]
 	fn message_handlers(&self) -> &MessageHandlers<MessageHandlerArguments, Result<(), DequeuedMessageProcessingError>>
 	{
 		&self.message_handlers
 	}
+	
+	#[inline(always)]
+	fn overflow_counters(&self) -> &OverflowCounters
+	{
+		&self.overflow_counters
+	}
+	
+	#[inline(always)]
+	fn messages_expired(&self) -> u64
+	{
+		self.messages_expired.load(Relaxed)
+	}
+	
+	/// Messages in the timer wheel which are now due are handled first, then messages in the compact lane, if any, and the priority lanes are dequeued by priority.
+	///
+	/// Within a lane, messages in its magic ring buffer are dequeued before those spilled to the heap.
+	#[inline(always)]
+	fn dequeue_from_timer_wheel_then_lanes(&self, terminate: &Arc<impl Terminate>, message_handler_arguments: &MessageHandlerArguments, dequeue_budget: &DequeueBudget, dequeued_message_processing_error_policy: &mut DequeuedMessageProcessingErrorPolicy<DequeuedMessageProcessingError>, dequeue_outcome: &mut DequeueOutcome) -> Result<DequeueStoppedBecause, DequeuedMessageProcessingError>
+	{
+		use self::DequeueStoppedBecause::*;
+		
+		if let Some(ref compact_lane) = self.compact_lane
+		{
+			compact_lane.evict_oldest_messages(self.message_handlers(), self.overflow_counters())
+		}
+		for priority_lane in self.priority_lanes.iter()
+		{
+			priority_lane.evict_oldest_messages(self.message_handlers(), self.overflow_counters())
+		}
+		
+		let started_at = dequeue_budget.started_at();
+		
+		if !self.timer_wheel.borrow().is_empty()
+		{
+			let now = MonotonicTime::now();
+			loop
+			{
+				if !terminate.should_continue()
+				{
+					return Ok(Terminated)
+				}
+				
+				if dequeue_budget.is_exhausted(started_at, dequeue_outcome)
+				{
+					return Ok(BudgetExhausted)
+				}
+				
+				let due_message = self.timer_wheel.borrow_mut().next_due_message(now);
+				match due_message
+				{
+					None => break,
+					
+					Some(mut scheduled_message) =>
+					{
+						let message_header_extensions = scheduled_message.message_header_extensions();
+						scheduled_message.process
+						(
+							|compressed_type_identifier, variably_sized_message_body|
+							{
+								if self.expire_if_expired(compressed_type_identifier, message_header_extensions, variably_sized_message_body, message_handler_arguments, dequeue_outcome)
+								{
+									return Ok(())
+								}
+								
+								self.call_and_drop_in_place(compressed_type_identifier, variably_sized_message_body, message_handler_arguments, dequeued_message_processing_error_policy, dequeue_outcome)
+							}
+						)?;
+						dequeue_outcome.record_message_dequeued(0)
+					}
+				}
+			}
+		}
+		
+		loop
+		{
+			if !terminate.should_continue()
+			{
+				return Ok(Terminated)
+			}
+			
+			if dequeue_budget.is_exhausted(started_at, dequeue_outcome)
+			{
+				return Ok(BudgetExhausted)
+			}
+			
+			let bytes_dequeued = self.priority_lane_starvation_guard.process_next_message
+			(
+				self.compact_lane.as_ref(),
+				&self.priority_lanes,
+				|compressed_type_identifier, message_header_extensions, variably_sized_message_body|
+				{
+					if self.expire_if_expired(compressed_type_identifier, message_header_extensions, variably_sized_message_body, message_handler_arguments, dequeue_outcome)
+					{
+						return Ok(())
+					}
+					
+					if self.schedule_if_not_yet_due(compressed_type_identifier, message_header_extensions, variably_sized_message_body, dequeue_outcome)
+					{
+						return Ok(())
+					}
+					
+					self.call_and_drop_in_place(compressed_type_identifier, variably_sized_message_body, message_handler_arguments, dequeued_message_processing_error_policy, dequeue_outcome)
+				}
+			)?;
+			
+			match bytes_dequeued
+			{
+				None => return Ok(QueueExhausted),
+				
+				Some(bytes_dequeued) => dequeue_outcome.record_message_dequeued(bytes_dequeued),
+			}
+		}
+	}
+	
+	/// If the message has a due time which is still in the future, moves it to the timer wheel and returns `true`.
+	#[inline(always)]
+	fn schedule_if_not_yet_due(&self, compressed_type_identifier: CompressedTypeIdentifier, message_header_extensions: MessageHeaderExtensions, variably_sized_message_body: NonNull<VariablySizedMessageBody>, dequeue_outcome: &mut DequeueOutcome) -> bool
+	{
+		let due_time = match message_header_extensions.due_time()
+		{
+			None => return false,
+			
+			Some(due_time) => due_time,
+		};
+		
+		let now = MonotonicTime::now();
+		if due_time <= now
+		{
+			return false
+		}
+		
+		let layout = self.message_handlers().layout(compressed_type_identifier, variably_sized_message_body);
+		self.timer_wheel.borrow_mut().schedule(now, ScheduledMessage::move_from(message_header_extensions, compressed_type_identifier, layout, variably_sized_message_body));
+		dequeue_outcome.record_message_scheduled();
+		true
+	}
+	
+	/// If the message has an expiry time which has passed, calls its expired message handler, if any, drops it in place and returns `true`.
+	///
+	/// If `catch_message_handler_panics`, a panicking expired message handler is counted in `dequeue_outcome` and its panic is kept in `message_handler_panics`.
+	#[inline(always)]
+	fn expire_if_expired(&self, compressed_type_identifier: CompressedTypeIdentifier, message_header_extensions: MessageHeaderExtensions, variably_sized_message_body: NonNull<VariablySizedMessageBody>, message_handler_arguments: &MessageHandlerArguments, dequeue_outcome: &mut DequeueOutcome) -> bool
+	{
+		let expiry_time = match message_header_extensions.expiry_time()
+		{
+			None => return false,
+			
+			Some(expiry_time) => expiry_time,
+		};
+		
+		if expiry_time > MonotonicTime::now()
+		{
+			return false
+		}
+		
+		self.messages_expired.fetch_add(1, Relaxed);
+		dequeue_outcome.record_message_expired();
+		if let Err(handler_panicked) = self.message_handlers().call_expired_message_handler_and_drop_in_place(compressed_type_identifier, variably_sized_message_body, message_handler_arguments, self.catch_message_handler_panics)
+		{
+			dequeue_outcome.record_message_handler_panicked();
+			self.message_handler_panics.borrow_mut().push(handler_panicked)
+		}
+		true
+	}
+	
+	/// Calls the message handler then drops the message in place, applying `dequeued_message_processing_error_policy` if the message handler failed.
+	///
+	/// If `catch_message_handler_panics`, a panicking message handler is counted in `dequeue_outcome`, its panic is kept in `message_handler_panics` and dequeuing continues.
+	///
+	/// If there is a dead letter sink, a message whose message handler failed or panicked is moved to it rather than dropped in place.
+	#[inline(always)]
+	fn call_and_drop_in_place(&self, compressed_type_identifier: CompressedTypeIdentifier, variably_sized_message_body: NonNull<VariablySizedMessageBody>, message_handler_arguments: &MessageHandlerArguments, dequeued_message_processing_error_policy: &mut DequeuedMessageProcessingErrorPolicy<DequeuedMessageProcessingError>, dequeue_outcome: &mut DequeueOutcome) -> Result<(), DequeuedMessageProcessingError>
+	{
+		let message_handlers = self.message_handlers();
+		
+		let result = if self.catch_message_handler_panics || message_handlers.has_dead_letter_sink()
+		{
+			match message_handlers.call_then_drop_in_place_or_dead_letter(compressed_type_identifier, variably_sized_message_body, message_handler_arguments, self.catch_message_handler_panics)
+			{
+				Ok(result) => result,
+				
+				Err(handler_panicked) =>
+				{
+					dequeue_outcome.record_message_handler_panicked();
+					self.message_handler_panics.borrow_mut().push(handler_panicked);
+					return Ok(())
+				}
+			}
+		}
+		else
+		{
+			message_handlers.call_and_drop_in_place(compressed_type_identifier, variably_sized_message_body, message_handler_arguments)
+		};
+		
+		match result
+		{
+			Ok(()) => Ok(()),
+			
+			Err(dequeued_message_processing_error) =>
+			{
+				dequeued_message_processing_error_policy.apply(compressed_type_identifier, dequeued_message_processing_error)?;
+				dequeue_outcome.record_message_handler_failed();
+				Ok(())
+			}
+		}
+	}
+	
+	/// Takes the panics of message handlers caught since they were last taken.
+	#[inline(always)]
+	fn take_message_handler_panics(&self) -> Vec<HandlerPanicked>
+	{
+		take(&mut *self.message_handler_panics.borrow_mut())
+	}
+	
+	/// A cheap, but possibly stale, measure of how loaded the queue is, used to choose the least loaded queue to publish to.
+	///
+	/// This is the number of bytes in the magic ring buffers of the compact lane and all priority lanes as a fraction of their capacity, from `0` (empty) to `Self::FullLoad`, so that queues of different sizes are compared fairly.
+	/// If messages have been spilled to the heap, the queue is considered to be as loaded as possible, `u64::MAX`.
+	#[inline(always)]
+	fn load(&self) -> u64
+	{
+		let lanes = self.compact_lane.iter().map(|compact_lane| (compact_lane.load(), compact_lane.capacity_in_bytes())).chain(self.priority_lanes.iter().map(|priority_lane| (priority_lane.load(), priority_lane.capacity_in_bytes())));
+		
+		let mut bytes_reserved = 0;
+		let mut capacity_in_bytes = 0;
+		for (load, lane_capacity_in_bytes) in lanes
+		{
+			if load == u64::MAX
+			{
+				return u64::MAX
+			}
+			bytes_reserved += load;
+			capacity_in_bytes += lane_capacity_in_bytes;
+		}
+		
+		((bytes_reserved as u128 * Self::FullLoad as u128) / (capacity_in_bytes as u128)) as u64
+	}
+	
+	/// Are there messages in either the compact lane or any priority lane?
+	#[inline(always)]
+	fn has_pending_messages(&self) -> bool
+	{
+		self.priority_lanes.iter().any(PriorityLane::has_pending_messages) || self.compact_lane.as_ref().map(CompactLane::has_pending_messages).unwrap_or(false)
+	}
+	
+	/// Parks the subscriber until a message is published, `timeout` elapses or `wake_subscriber()` is called.
+	///
+	/// Returns immediately if there are pending messages.
+	///
+	/// Whilst there are messages in the timer wheel, parks for no longer than until the next of them is due, so that they are handled once due.
+	#[inline(always)]
+	fn park_subscriber(&self, timeout: Option<Duration>)
+	{
+		let timeout = match self.next_scheduled_message_due_at()
+		{
+			None => timeout,
+			
+			Some(next_scheduled_message_due_at) =>
+			{
+				let until_due = Duration::from_nanos(next_scheduled_message_due_at.nanoseconds().saturating_sub(MonotonicTime::now().nanoseconds()));
+				Some(timeout.map(|timeout| min(timeout, until_due)).unwrap_or(until_due))
+			}
+		};
+		
+		self.subscriber_wake_up.park(|| self.has_pending_messages(), timeout)
+	}
+	
+	/// The time at or after which the next message in the timer wheel is due, if any; it is in the past if a message is already due.
+	#[inline(always)]
+	fn next_scheduled_message_due_at(&self) -> Option<MonotonicTime>
+	{
+		self.timer_wheel.borrow().next_due_time()
+	}
+	
+	/// Are there messages in the timer wheel which are now due?
+	#[inline(always)]
+	fn has_due_scheduled_messages(&self) -> bool
+	{
+		match self.next_scheduled_message_due_at()
+		{
+			None => false,
+			
+			Some(next_scheduled_message_due_at) => next_scheduled_message_due_at <= MonotonicTime::now(),
+		}
+	}
+	
+	/// Records the thread of the (only) subscriber, which is the only thread that can ever make space in the queue.
+	#[inline(always)]
+	fn register_subscriber_thread(&self)
+	{
+		self.subscriber_thread.store(unsafe { pthread_self() } as u64, Release)
+	}
+	
+	#[inline(always)]
+	fn is_subscriber_thread(&self) -> bool
+	{
+		self.subscriber_thread.load(Acquire) == unsafe { pthread_self() } as u64
+	}
+	
+	/// Wakes the subscriber if it is parked.
+	#[inline(always)]
+	fn wake_subscriber(&self)
+	{
+		self.subscriber_wake_up.wake_if_parked()
+	}
+	
+	/// Writes a message to a priority lane's magic ring buffer once space has been reserved for it.
+	///
+	/// Wakes the subscriber if this message made the priority lane non-empty.
+	#[inline(always)]
+	fn enqueue_in_magic_ring_buffer<FixedSizeMessageBody: Sized>(&self, priority_lane: &PriorityLane, previous_bytes_reserved: u64, bytes_needed: usize, fixed_sized_message_body_compressed_type_identifier: CompressedTypeIdentifier, message_header_extensions: MessageHeaderExtensions, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>))
+	{
+		MessageRepresentation::enqueue(&priority_lane.magic_ring_buffer, bytes_needed, fixed_sized_message_body_compressed_type_identifier, message_header_extensions, fixed_size_message_body_constructor);
+		
+		let queue_was_empty = previous_bytes_reserved == 0;
+		if queue_was_empty
+		{
+			self.queue_became_non_empty()
+		}
+	}
+	
+	/// Registers the waker of an `AsyncSubscriber`.
+	#[inline(always)]
+	fn register_subscriber_waker(&self, waker: &Waker)
+	{
+		self.subscriber_waker.register(waker)
+	}
+	
+	/// Wakes the subscriber if it is parked or has registered a waker, and makes the eventfd, if any, readable.
+	#[inline(always)]
+	fn queue_became_non_empty(&self)
+	{
+		self.wake_subscriber();
+		self.subscriber_waker.wake_if_registered();
+		if let Some(ref event_file_descriptor) = self.event_file_descriptor
+		{
+			event_file_descriptor.signal()
+		}
+	}
+	
+	#[inline(always)]
+	fn acknowledge_event_file_descriptor(&self)
+	{
+		if let Some(ref event_file_descriptor) = self.event_file_descriptor
+		{
+			event_file_descriptor.acknowledge()
+		}
+	}
+	
+	/// Messages in the timer wheel count as pending only once they are due; the eventfd is not signalled when they become due.
+	#[inline(always)]
+	fn signal_event_file_descriptor_if_messages_are_still_pending(&self)
+	{
+		if let Some(ref event_file_descriptor) = self.event_file_descriptor
+		{
+			if self.has_pending_messages() || self.has_due_scheduled_messages()
+			{
+				event_file_descriptor.signal()
+			}
+		}
+	}
+	
+	/// The eventfd, if any.
+	#[inline(always)]
+	fn event_file_descriptor(&self) -> Option<RawFd>
+	{
+		self.event_file_descriptor.as_ref().map(QueueEventFileDescriptor::as_raw_fd)
+	}
+	
+	/// The total size of a message, including its `MessageHeader`, `message_header_extensions` and `trailing_bytes`.
+	///
+	/// Panics if the message is too large or too aligned to be represented or could never fit in the priority lane's magic ring buffer, as otherwise enqueuing it would corrupt the magic ring buffer or wait forever.
+	#[inline(always)]
+	fn bytes_needed<FixedSizeMessageBody: Sized>(&self, priority_lane: &PriorityLane, message_header_extensions: MessageHeaderExtensions, trailing_bytes: usize) -> usize
+	{
+		let bytes_needed = MessageRepresentation::total_message_size_including_message_header::<FixedSizeMessageBody>(message_header_extensions, trailing_bytes);
+		assert!(bytes_needed <= MessageRepresentation::MaximumTotalMessageSize, "Message of `{}` bytes is far too large", bytes_needed);
+		assert!(align_of::<FixedSizeMessageBody>() <= MessageRepresentation::MaximumMessageBodyAlignment, "Message is aligned to more than a page");
+		assert!(priority_lane.queue_occupancy.can_ever_fit(bytes_needed), "Message of `{}` bytes is larger than the queue", bytes_needed);
+		bytes_needed
+	}
+	
+	/// As for `bytes_needed()`, but returns `Err(bytes_needed)` rather than panicking if the message could never be enqueued, so that publishing without waiting never panics.
+	#[inline(always)]
+	fn bytes_needed_if_could_ever_fit<FixedSizeMessageBody: Sized>(&self, priority_lane: &PriorityLane, message_header_extensions: MessageHeaderExtensions, trailing_bytes: usize) -> Result<usize, usize>
+	{
+		let bytes_needed = MessageRepresentation::total_message_size_including_message_header::<FixedSizeMessageBody>(message_header_extensions, trailing_bytes);
+		if bytes_needed <= MessageRepresentation::MaximumTotalMessageSize && align_of::<FixedSizeMessageBody>() <= MessageRepresentation::MaximumMessageBodyAlignment && priority_lane.queue_occupancy.can_ever_fit(bytes_needed)
+		{
+			Ok(bytes_needed)
+		}
+		else
+		{
+			Err(bytes_needed)
+		}
+	}
+	
+	/// Constructs a message on the heap and appends it to a priority lane's overflow list.
+	#[inline(always)]
+	fn spill<FixedSizeMessageBody: Sized>(&self, priority_lane: &PriorityLane, bytes_needed: usize, fixed_sized_message_body_compressed_type_identifier: CompressedTypeIdentifier, message_header_extensions: MessageHeaderExtensions, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>))
+	{
+		priority_lane.push_spilled_message(Self::spilled_message(bytes_needed, fixed_sized_message_body_compressed_type_identifier, message_header_extensions, fixed_size_message_body_constructor));
+		
+		self.overflow_counters.increment_spilled_to_heap();
+		self.queue_became_non_empty()
+	}
+	
+	/// As for `spill()`, but applies the `DropOldest` overflow policy.
+	///
+	/// As many bytes of the oldest messages in the magic ring buffer are evicted at the next dequeue; and, so that memory stays bounded if the subscriber does not dequeue, the oldest messages in the overflow list are evicted now whilst it holds more bytes than the magic ring buffer can.
+	#[inline(always)]
+	fn spill_dropping_oldest<FixedSizeMessageBody: Sized>(&self, priority_lane: &PriorityLane, bytes_needed: usize, fixed_sized_message_body_compressed_type_identifier: CompressedTypeIdentifier, message_header_extensions: MessageHeaderExtensions, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>))
+	{
+		priority_lane.evict_oldest_messages_later(bytes_needed);
+		priority_lane.push_spilled_message_dropping_oldest(Self::spilled_message(bytes_needed, fixed_sized_message_body_compressed_type_identifier, message_header_extensions, fixed_size_message_body_constructor), self.message_handlers(), self.overflow_counters());
+		
+		self.overflow_counters.increment_spilled_to_heap();
+		self.queue_became_non_empty()
+	}
+	
+	/// Constructs a message on the heap.
+	#[inline(always)]
+	fn spilled_message<FixedSizeMessageBody: Sized>(bytes_needed: usize, fixed_sized_message_body_compressed_type_identifier: CompressedTypeIdentifier, message_header_extensions: MessageHeaderExtensions, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>)) -> SpilledMessage
+	{
+		SpilledMessage::new(bytes_needed, |buffer_sized_as_for_maximum_possible| MessageRepresentation::enqueue_once_buffer_allocated(buffer_sized_as_for_maximum_possible, fixed_sized_message_body_compressed_type_identifier, message_header_extensions, fixed_size_message_body_constructor))
+	}
 }
@@ -0,0 +1,48 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// An error when creating a `Queue`.
+#[derive(Debug)]
+pub enum QueueCreationError
+{
+	/// Could not create the magic ring buffer of a priority lane or of the compact lane.
+	MirroredMemoryMapCreation(MirroredMemoryMapCreationError),
+	
+	/// Could not create the eventfd requested by `QueueSettings.event_file_descriptor`.
+	EventFileDescriptorCreation(io::Error),
+}
+
+impl fmt::Display for QueueCreationError
+{
+	#[inline(always)]
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result
+	{
+		Debug::fmt(self, f)
+	}
+}
+
+impl error::Error for QueueCreationError
+{
+	#[inline(always)]
+	fn source(&self) -> Option<&(dyn error::Error + 'static)>
+	{
+		use self::QueueCreationError::*;
+		
+		match self
+		{
+			&MirroredMemoryMapCreation(ref error) => Some(error),
+			
+			&EventFileDescriptorCreation(ref error) => Some(error),
+		}
+	}
+}
+
+impl From<MirroredMemoryMapCreationError> for QueueCreationError
+{
+	#[inline(always)]
+	fn from(error: MirroredMemoryMapCreationError) -> Self
+	{
+		QueueCreationError::MirroredMemoryMapCreation(error)
+	}
+}
@@ -0,0 +1,66 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// An eventfd, owned by a queue, which is readable whenever messages are pending.
+///
+/// Suitable for registering with epoll (it is non-blocking and is never written to by a reactor).
+///
+/// It is signalled by publishers when the queue transitions from empty to non-empty, acknowledged by the subscriber before it dequeues and signalled again by the subscriber if messages are still pending once it has finished dequeuing.
+#[derive(Debug)]
+struct QueueEventFileDescriptor(RawFd);
+
+impl Drop for QueueEventFileDescriptor
+{
+	#[inline(always)]
+	fn drop(&mut self)
+	{
+		unsafe { close(self.0) };
+	}
+}
+
+impl AsRawFd for QueueEventFileDescriptor
+{
+	#[inline(always)]
+	fn as_raw_fd(&self) -> RawFd
+	{
+		self.0
+	}
+}
+
+impl QueueEventFileDescriptor
+{
+	#[inline(always)]
+	fn new() -> io::Result<Self>
+	{
+		let result = unsafe { eventfd(0, EFD_NONBLOCK | EFD_CLOEXEC) };
+		if result == -1
+		{
+			Err(io::Error::last_os_error())
+		}
+		else
+		{
+			Ok(Self(result))
+		}
+	}
+	
+	/// Makes the eventfd readable.
+	///
+	/// Can not block; if the eventfd's counter would overflow, it is already readable.
+	#[inline(always)]
+	fn signal(&self)
+	{
+		const Increment: u64 = 1;
+		let result = unsafe { libc_write(self.0, &Increment as *const u64 as *const c_void, size_of::<u64>()) };
+		debug_assert!(result == size_of::<u64>() as isize || unsafe { *__errno_location() } == EAGAIN, "Unexpected error writing to eventfd");
+	}
+	
+	/// Makes the eventfd unreadable (until it is next signalled).
+	#[inline(always)]
+	fn acknowledge(&self)
+	{
+		let mut counter: u64 = 0;
+		let result = unsafe { read(self.0, &mut counter as *mut u64 as *mut c_void, size_of::<u64>()) };
+		debug_assert!(result == size_of::<u64>() as isize || unsafe { *__errno_location() } == EAGAIN, "Unexpected error reading from eventfd");
+	}
+}
@@ -0,0 +1,92 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// Tracks the number of bytes enqueued but not yet dequeued for a `Queue`.
+///
+/// Accounting is conservative: bytes are reserved before a message is written and released only once it has been dequeued.
+/// Consequently, a successful `try_reserve()` guarantees that there is space in the underlying magic ring buffer, which is never smaller than `capacity_in_bytes`.
+#[derive(Debug)]
+struct QueueOccupancy
+{
+	capacity_in_bytes: u64,
+	bytes_reserved: AtomicU64,
+}
+
+impl QueueOccupancy
+{
+	#[inline(always)]
+	fn new(capacity_in_bytes: NonZeroU64) -> Self
+	{
+		Self
+		{
+			capacity_in_bytes: capacity_in_bytes.get(),
+			bytes_reserved: AtomicU64::new(0),
+		}
+	}
+	
+	/// Reserves `bytes_needed` if doing so would not exceed capacity.
+	///
+	/// Returns the number of bytes previously reserved if successful; `Some(0)` means the queue has transitioned from empty to non-empty.
+	#[inline(always)]
+	fn try_reserve(&self, bytes_needed: usize) -> Option<u64>
+	{
+		let bytes_needed = bytes_needed as u64;
+		let mut bytes_reserved = self.bytes_reserved.load(Relaxed);
+		loop
+		{
+			let new_bytes_reserved = bytes_reserved + bytes_needed;
+			if new_bytes_reserved > self.capacity_in_bytes
+			{
+				return None
+			}
+			
+			match self.bytes_reserved.compare_exchange_weak(bytes_reserved, new_bytes_reserved, SeqCst, Relaxed)
+			{
+				Ok(previous_bytes_reserved) => return Some(previous_bytes_reserved),
+				
+				Err(was_bytes_reserved) => bytes_reserved = was_bytes_reserved,
+			}
+		}
+	}
+	
+	/// Could a message of `bytes_needed` ever be reserved, ie once the queue is empty?
+	#[inline(always)]
+	fn can_ever_fit(&self, bytes_needed: usize) -> bool
+	{
+		(bytes_needed as u64) <= self.capacity_in_bytes
+	}
+	
+	#[inline(always)]
+	fn capacity_in_bytes(&self) -> u64
+	{
+		self.capacity_in_bytes
+	}
+	
+	/// A cheap, but possibly stale, measure of how full the queue is.
+	#[inline(always)]
+	fn bytes_reserved(&self) -> u64
+	{
+		self.bytes_reserved.load(Relaxed)
+	}
+	
+	/// Are there any bytes reserved?
+	///
+	/// Bytes are reserved before a message is visible in the magic ring buffer, so this may be `true` momentarily before a message can be dequeued.
+	#[inline(always)]
+	fn is_not_empty(&self) -> bool
+	{
+		self.bytes_reserved.load(SeqCst) != 0
+	}
+	
+	/// Releases bytes once a message has been dequeued (or dropped).
+	#[inline(always)]
+	fn release(&self, bytes_dequeued: usize)
+	{
+		if bytes_dequeued != 0
+		{
+			let was_bytes_reserved = self.bytes_reserved.fetch_sub(bytes_dequeued as u64, SeqCst);
+			debug_assert!(was_bytes_reserved >= bytes_dequeued as u64, "Released more bytes than were reserved");
+		}
+	}
+}
@@ -0,0 +1,69 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// Settings for the queue for a particular hyper thread.
+///
+/// Returned for each hyper thread from the closure passed to `Queues::one_queue_for_each_hyper_thread()`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct QueueSettings
+{
+	/// The queue is sized to hold this many of the largest possible message registered with its `MessageHandlers`.
+	pub preferred_maximum_number_of_elements_of_largest_possible_fixed_size_message_body: NonZeroU64,
+	
+	/// What to do when publishing to a full queue.
+	pub overflow_policy: OverflowPolicy,
+	
+	/// Should the queue own an eventfd which is readable whenever messages are pending?
+	///
+	/// Use `Subscriber::as_raw_fd()` to register it with an epoll reactor.
+	/// Publishers make an additional system call when the queue transitions from empty to non-empty, and the subscriber makes one or two additional system calls each time it dequeues.
+	pub event_file_descriptor: bool,
+	
+	/// Should a panic in a message handler be caught?
+	///
+	/// If `true`, the panicking message is dropped in place, the queue's read position is advanced past it, dequeuing continues and the panic is counted in `DequeueOutcome.message_handlers_panicked` and kept until taken with `Subscriber::take_message_handler_panics()`.
+	/// This stops one bad message type taking down a pinned worker thread, at the cost of a landing pad around every message handler call.
+	///
+	/// If `false`, a panic unwinds out of receiving and handling messages and the state of the queue is unspecified; it should not be used again.
+	pub catch_message_handler_panics: bool,
+	
+	/// Should the queue have a compact lane for messages published with a `CompactPublisher`, and, if so, how many messages should it hold?
+	///
+	/// Each message in the compact lane is a single 8 byte word with no `MessageHeader`.
+	/// The compact lane has a higher priority than the first priority lane, and counts as a higher priority lane for the `priority_lane_starvation_guard`.
+	///
+	/// Ignored unless a compact message handler is registered using `MessageHandlers::register_compact_message_handler()`.
+	pub compact_lane_maximum_number_of_messages: Option<NonZeroU64>,
+	
+	/// How many priority lanes should the queue have?
+	///
+	/// Each priority lane has its own magic ring buffer of the same size, so memory use is proportional to the number of priority lanes.
+	/// Messages published with `Priority(0)` go to the first, highest priority lane.
+	pub number_of_priority_lanes: NonZeroU8,
+	
+	/// If there is more than one priority lane, the maximum number of messages dequeued in a row from higher priority lanes whilst a lower priority lane has pending messages.
+	///
+	/// Once reached, one message is dequeued from a lower priority lane; lower priority lanes take turns.
+	/// If `None`, lower priority lanes are only dequeued from once all higher priority lanes are empty, and so can be starved.
+	pub priority_lane_starvation_guard: Option<NonZeroU64>,
+}
+
+impl QueueSettings
+{
+	/// New instance with a default `OverflowPolicy`, without an eventfd, without catching message handler panics, without a compact lane and with just one priority lane.
+	#[inline(always)]
+	pub fn new(preferred_maximum_number_of_elements_of_largest_possible_fixed_size_message_body: NonZeroU64) -> Self
+	{
+		Self
+		{
+			preferred_maximum_number_of_elements_of_largest_possible_fixed_size_message_body,
+			overflow_policy: OverflowPolicy::default(),
+			event_file_descriptor: false,
+			catch_message_handler_panics: false,
+			compact_lane_maximum_number_of_messages: None,
+			number_of_priority_lanes: unsafe { NonZeroU8::new_unchecked(1) },
+			priority_lane_starvation_guard: None,
+		}
+	}
+}
@@ -3,8 +3,10 @@
 
 
 /// Pass cloned copies of this to each thread at initialization.
+///
+/// Also holds every hyper thread with a queue.
 #[derive(Debug)]
-pub struct Queues<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>(Arc<PerBitSetAwareData<HyperThread, Queue<MessageHandlerArguments, DequeuedMessageProcessingError>>>);
+pub struct Queues<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>(Arc<PerBitSetAwareData<HyperThread, Queue<MessageHandlerArguments, DequeuedMessageProcessingError>>>, Arc<[HyperThread]>);
 
 unsafe impl<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> Send for Queues<MessageHandlerArguments, DequeuedMessageProcessingError>
 {
@@ -19,7 +21,7 @@ impl<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> Clon
 	#[inline(always)]
 	fn clone(&self) -> Self
 	{
-		Self(self.0.clone())
+		Self(self.0.clone(), self.1.clone())
 	}
 }
 
@@ -28,26 +30,65 @@ impl<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> Queu
 	/// One way to estimate `queue_size_in_bytes` is to find the largest `size_of::<>()` of all types of `FixedSizeMessageBody` in `message_handlers`.
 	/// `message_handlers()` is invoked from the calling thread, not the thread that will then invoke the message handlers.
 	/// This means that they should be allocated from global memory and will *not* be NUMA aware (or will steal from the calling thread's NUMA memory).
+	///
+	/// `QueueSettings` allow each hyper thread's queue to have a different `OverflowPolicy`, eg so control-plane traffic is never lost but telemetry traffic is lossy.
+	///
+	/// Fails if any queue could not be created, eg because its magic ring buffer could not be memory mapped or its eventfd could not be created.
 	#[inline(always)]
-	pub fn one_queue_for_each_hyper_thread(hyper_threads: &BitSet<HyperThread>, message_handlers_and_preferred_maximum_number_of_elements_of_largest_possible_fixed_size_message_body_in_queue_for_hyper_thread
-	: &impl Fn(HyperThread) -> (MessageHandlers<MessageHandlerArguments, Result<(), DequeuedMessageProcessingError>>, NonZeroU64), defaults: &DefaultHugePageSizes, inclusive_maximum_bytes_wasted: u64) -> Self
+	pub fn one_queue_for_each_hyper_thread(hyper_threads: &BitSet<HyperThread>, message_handlers_and_queue_settings_for_hyper_thread: &impl Fn(HyperThread) -> (MessageHandlers<MessageHandlerArguments, Result<(), DequeuedMessageProcessingError>>, QueueSettings), defaults: &DefaultHugePageSizes, inclusive_maximum_bytes_wasted: u64) -> Result<Self, QueueCreationError>
 	{
-		Self
+		// `PerBitSetAwareData::new()` can not fail, so every queue is created (and any failure returned) first.
+		let created_queues = RefCell::new(Vec::new());
+		PerBitSetAwareData::new
 		(
-			Arc::new
-			(
-				PerBitSetAwareData::new
-				(
-					hyper_threads,
-					|hyper_thread|
-					{
-						let (message_handlers, preferred_maximum_number_of_elements_of_largest_possible_fixed_size_message_body) = message_handlers_and_preferred_maximum_number_of_elements_of_largest_possible_fixed_size_message_body_in_queue_for_hyper_thread(hyper_thread);
-						let queue_size_in_bytes = message_handlers.queue_size_in_bytes(preferred_maximum_number_of_elements_of_largest_possible_fixed_size_message_body);
-						Queue::new(message_handlers, defaults, queue_size_in_bytes, inclusive_maximum_bytes_wasted).unwrap()
-					}
-				)
-			)
-		)
+			hyper_threads,
+			|hyper_thread|
+			{
+				let (message_handlers, queue_settings) = message_handlers_and_queue_settings_for_hyper_thread(hyper_thread);
+				let queue_size_in_bytes = message_handlers.queue_size_in_bytes(queue_settings.preferred_maximum_number_of_elements_of_largest_possible_fixed_size_message_body);
+				created_queues.borrow_mut().push((hyper_thread, Queue::new(message_handlers, defaults, queue_size_in_bytes, inclusive_maximum_bytes_wasted, &queue_settings)))
+			}
+		);
+		
+		let mut hyper_threads_with_queues = Vec::new();
+		let mut queues_in_hyper_thread_order = Vec::new();
+		for (hyper_thread, queue) in created_queues.into_inner()
+		{
+			hyper_threads_with_queues.push(hyper_thread);
+			queues_in_hyper_thread_order.push(queue?);
+		}
+		
+		let queues_in_hyper_thread_order = RefCell::new(queues_in_hyper_thread_order.into_iter());
+		let queues = PerBitSetAwareData::new(hyper_threads, |_hyper_thread| queues_in_hyper_thread_order.borrow_mut().next().unwrap());
+		
+		Ok(Self(Arc::new(queues), hyper_threads_with_queues.into()))
+	}
+	
+	/// Every hyper thread with a queue.
+	#[inline(always)]
+	pub fn hyper_threads(&self) -> &[HyperThread]
+	{
+		&self.1
+	}
+	
+	/// Does `hyper_thread` have a queue?
+	#[inline(always)]
+	pub fn has_queue(&self, hyper_thread: HyperThread) -> bool
+	{
+		self.1.contains(&hyper_thread)
+	}
+	
+	/// Panics if `hyper_threads_to_publish_to` is empty or if any of them does not have a queue.
+	///
+	/// Used by publishers which choose amongst a set of hyper threads, which would otherwise silently publish to their default hyper thread instead.
+	#[inline(always)]
+	fn assert_hyper_threads_to_publish_to_have_queues(&self, hyper_threads_to_publish_to: &[HyperThread])
+	{
+		assert!(!hyper_threads_to_publish_to.is_empty(), "There must be at least one hyper thread to publish to");
+		for &hyper_thread in hyper_threads_to_publish_to
+		{
+			assert!(self.has_queue(hyper_thread), "Hyper thread `{:?}` to publish to does not have a queue", hyper_thread)
+		}
 	}
 	
 	/// New publisher.
@@ -57,15 +98,149 @@ impl<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> Queu
 		Publisher::new(self, default_hyper_thread)
 	}
 	
+	/// New publisher of a member, `M`, of a `MessageSet`, `Set`.
+	///
+	/// For queues whose `MessageHandlers` registered `Set` using `register_message_set()`, the compressed type identifier is the one assigned at compile time, rather than being looked up.
+	#[inline(always)]
+	pub fn message_set_publisher<Set: MessageSet<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, M: 'static + MessageSetMember<Set>>(&self, default_hyper_thread: HyperThread) -> Publisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>
+	{
+		Publisher::new_for_message_set_member::<Set>(self, default_hyper_thread)
+	}
+	
+	/// New publisher of values, which are moved into a queue.
+	///
+	/// `FixedSizeMessageBody` must have been registered using `MessageHandlers::register_value_message_handler()` (or `MessageHandlers::register_message_handler()`).
+	#[inline(always)]
+	pub fn value_publisher<FixedSizeMessageBody: 'static + Sized>(&self, default_hyper_thread: HyperThread) -> ValuePublisher<FixedSizeMessageBody, MessageHandlerArguments, DequeuedMessageProcessingError>
+	{
+		ValuePublisher::new(self, default_hyper_thread)
+	}
+	
+	/// New publisher of messages, `M`, which conflates undelivered messages by `Key`.
+	///
+	/// `M` must have been registered using `MessageHandlers::register_conflated_message_handler()`.
+	#[inline(always)]
+	pub fn conflating_publisher<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, Key: 'static + Hash + Eq + Clone>(&self, default_hyper_thread: HyperThread) -> ConflatingPublisher<M, Key, MessageHandlerArguments, DequeuedMessageProcessingError>
+	{
+		ConflatingPublisher::new(self, default_hyper_thread)
+	}
+	
+	/// New publisher of `Box<T>` with an opcode, which are enqueued in the compact lane of a queue if it has one.
+	///
+	/// `T` must have been registered using `MessageHandlers::register_compact_message_handler()`.
+	#[inline(always)]
+	pub fn compact_publisher<T: 'static>(&self, default_hyper_thread: HyperThread) -> CompactPublisher<T, MessageHandlerArguments, DequeuedMessageProcessingError>
+	{
+		CompactPublisher::new(self, default_hyper_thread)
+	}
+	
+	/// New publisher of values of any type implementing a trait, `Trait`, for which `TraitObjectMessage` is implemented (eg `dyn MyTrait`).
+	#[inline(always)]
+	pub fn trait_object_publisher<Trait: ?Sized + TraitObjectMessage<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>>(&self, default_hyper_thread: HyperThread) -> TraitObjectPublisher<Trait, MessageHandlerArguments, DequeuedMessageProcessingError>
+	{
+		TraitObjectPublisher::new(self, default_hyper_thread)
+	}
+	
+	/// New publisher of variably sized messages.
+	#[inline(always)]
+	pub fn variably_sized_publisher<Header: 'static + VariablySizedMessage<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>>(&self, default_hyper_thread: HyperThread) -> VariablySizedPublisher<Header, MessageHandlerArguments, DequeuedMessageProcessingError>
+	{
+		VariablySizedPublisher::new(self, default_hyper_thread)
+	}
+	
+	/// New publisher of variably sized messages with a string payload.
+	#[inline(always)]
+	pub fn str_publisher<Header: 'static + VariablySizedStrMessage<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>>(&self, default_hyper_thread: HyperThread) -> StrPublisher<Header, MessageHandlerArguments, DequeuedMessageProcessingError>
+	{
+		StrPublisher::new(self, default_hyper_thread)
+	}
+	
 	/// New round-robin publisher.
 	///
 	/// Loops infinitely around a set (`hyper_threads_to_publish_to`) of `HyperThread`s to publish to.
+	///
+	/// Panics if there are no hyper threads to publish to or one of them does not have a queue.
 	#[inline(always)]
 	pub fn round_robin_publisher<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>>(&self, hyper_threads_to_publish_to: Box<[HyperThread]>) -> RoundRobinPublisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>
 	{
 		RoundRobinPublisher::new(self, hyper_threads_to_publish_to)
 	}
 	
+	/// New weighted round-robin publisher.
+	///
+	/// Each of a set (`hyper_threads_to_publish_to_and_weights`) of `HyperThread`s receives a share of messages in proportion to its weight.
+	///
+	/// Panics if there are no hyper threads to publish to or one of them does not have a queue.
+	#[inline(always)]
+	pub fn weighted_round_robin_publisher<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>>(&self, hyper_threads_to_publish_to_and_weights: Box<[(HyperThread, NonZeroU32)]>) -> WeightedRoundRobinPublisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>
+	{
+		WeightedRoundRobinPublisher::new(self, hyper_threads_to_publish_to_and_weights)
+	}
+	
+	/// New round-robin publisher which can be shared by several publishing threads.
+	///
+	/// Loops infinitely around a set (`hyper_threads_to_publish_to`) of `HyperThread`s to publish to.
+	///
+	/// Panics if there are no hyper threads to publish to or one of them does not have a queue.
+	#[inline(always)]
+	pub fn shared_round_robin_publisher<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>>(&self, hyper_threads_to_publish_to: Box<[HyperThread]>) -> SharedRoundRobinPublisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>
+	{
+		SharedRoundRobinPublisher::new(self, hyper_threads_to_publish_to)
+	}
+	
+	/// New broadcast publisher.
+	///
+	/// Publishes to every queue.
+	#[inline(always)]
+	pub fn broadcast_publisher<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>>(&self) -> BroadcastPublisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>
+	{
+		BroadcastPublisher::new(self, self.hyper_threads().to_vec().into_boxed_slice())
+	}
+	
+	/// New broadcast publisher.
+	///
+	/// Publishes to every one of a set (`hyper_threads_to_publish_to`) of `HyperThread`s.
+	///
+	/// Panics if `hyper_threads_to_publish_to` is empty, contains duplicates or contains a hyper thread without a queue.
+	#[inline(always)]
+	pub fn broadcast_publisher_to<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>>(&self, hyper_threads_to_publish_to: Box<[HyperThread]>) -> BroadcastPublisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>
+	{
+		BroadcastPublisher::new(self, hyper_threads_to_publish_to)
+	}
+	
+	/// New key-affinity (sharded) publisher.
+	///
+	/// Maps keys to one of a set (`hyper_threads_to_publish_to`) of `HyperThread`s using consistent hashing with `virtual_nodes_per_hyper_thread` points on the ring for each hyper thread; more points spread keys more evenly.
+	///
+	/// Panics if `hyper_threads_to_publish_to` is empty or contains a hyper thread without a queue.
+	#[inline(always)]
+	pub fn sharded_publisher<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, K: Hash>(&self, hyper_threads_to_publish_to: Box<[HyperThread]>, virtual_nodes_per_hyper_thread: NonZeroUsize) -> ShardedPublisher<M, K, MessageHandlerArguments, DequeuedMessageProcessingError>
+	{
+		ShardedPublisher::new(self, hyper_threads_to_publish_to, virtual_nodes_per_hyper_thread)
+	}
+	
+	/// New least-loaded publisher.
+	///
+	/// Publishes to whichever of a set (`hyper_threads_to_publish_to`) of `HyperThread`s has the least loaded queue.
+	///
+	/// Panics if `hyper_threads_to_publish_to` is empty or contains a hyper thread without a queue.
+	#[inline(always)]
+	pub fn least_loaded_publisher<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>>(&self, hyper_threads_to_publish_to: Box<[HyperThread]>) -> LeastLoadedPublisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>
+	{
+		LeastLoadedPublisher::new(self, hyper_threads_to_publish_to)
+	}
+	
+	/// New power-of-two-choices publisher.
+	///
+	/// Publishes to the less loaded queue of two hyper threads chosen at random from a set (`hyper_threads_to_publish_to`) of `HyperThread`s.
+	///
+	/// Panics if `hyper_threads_to_publish_to` is empty or contains a hyper thread without a queue.
+	#[inline(always)]
+	pub fn power_of_two_choices_publisher<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>>(&self, hyper_threads_to_publish_to: Box<[HyperThread]>) -> PowerOfTwoChoicesPublisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>
+	{
+		PowerOfTwoChoicesPublisher::new(self, hyper_threads_to_publish_to)
+	}
+	
 	/// A publisher publishes to a specific hyper thread.
 	///
 	/// ***SLOW*** as it uses a hash map look up.
@@ -75,13 +250,30 @@ impl<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> Queu
 	///
 	/// Prefer `publisher().publish()` to this method.
 	///
+	/// If `M` is not registered for the hyper thread's queue, it is moved to the queue's `DeadLetterSink` with `DeadLetterReason::Unregistered`; if there is no `DeadLetterSink`, panics.
+	///
 	/// Returns the actual hyper thread published to.
 	pub fn publish_safe_but_slow<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>>(&self, hyper_thread: HyperThread, default_hyper_thread: HyperThread, construct_message_arguments: M::ConstructMessageArguments) -> HyperThread
 	{
 		let (queue, actual_hyper_thread) = self.0.get_or(hyper_thread, default_hyper_thread);
-		let fixed_sized_message_body_compressed_type_identifier = queue.fixed_sized_message_body_compressed_type_identifier::<M>();
-		unsafe { queue.enqueue(fixed_sized_message_body_compressed_type_identifier, |uninitialized_memory| M::construct_message(uninitialized_memory, construct_message_arguments)) };
-		actual_hyper_thread
+		Publisher::<M, MessageHandlerArguments, DequeuedMessageProcessingError>::publish_to_queue(queue, actual_hyper_thread, queue.fixed_sized_message_body_compressed_type_identifier::<M>(), Priority::Highest, MessageHeaderExtensions::default(), construct_message_arguments)
+	}
+	
+	/// A publisher publishes to a specific hyper thread without waiting for space in the queue.
+	///
+	/// ***SLOW*** as it uses a hash map look up.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	///
+	/// Prefer `publisher().try_publish()` to this method.
+	///
+	/// If `M` is not registered for the hyper thread's queue, it is moved to the queue's `DeadLetterSink` with `DeadLetterReason::Unregistered` and `PublishError::DeadLettered` is returned; if there is no `DeadLetterSink`, `PublishError::Unregistered` is returned.
+	///
+	/// Returns the actual hyper thread published to, or, if its queue is full, a `PublishError::QueueFull` which hands back the unconsumed `construct_message_arguments`.
+	pub fn try_publish_safe_but_slow<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>>(&self, hyper_thread: HyperThread, default_hyper_thread: HyperThread, construct_message_arguments: M::ConstructMessageArguments) -> Result<HyperThread, PublishError<M::ConstructMessageArguments>>
+	{
+		let (queue, actual_hyper_thread) = self.0.get_or(hyper_thread, default_hyper_thread);
+		Publisher::<M, MessageHandlerArguments, DequeuedMessageProcessingError>::try_publish_to_queue(queue, actual_hyper_thread, queue.fixed_sized_message_body_compressed_type_identifier::<M>(), Priority::Highest, MessageHeaderExtensions::default(), construct_message_arguments)
 	}
 	
 	/// Only works for the current hyper thread.
@@ -90,4 +282,53 @@ impl<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> Queu
 	{
 		Subscriber::new(self, for_hyper_thread)
 	}
+	
+	/// Only works for the current hyper thread.
+	///
+	/// Use instead of `subscriber()` inside an async executor.
+	#[inline(always)]
+	pub fn async_subscriber(&self, for_hyper_thread: HyperThread) -> AsyncSubscriber<MessageHandlerArguments, DequeuedMessageProcessingError>
+	{
+		AsyncSubscriber::new(self, for_hyper_thread)
+	}
+	
+	/// A cheap, but possibly stale, measure of how loaded the queue for `hyper_thread` is, as a fraction of its capacity.
+	///
+	/// `hyper_thread` must have a queue.
+	#[inline(always)]
+	fn load(&self, hyper_thread: HyperThread) -> u64
+	{
+		self.0.get_unchecked_safe(hyper_thread).load()
+	}
+	
+	/// Counters of the number of times the queue's `OverflowPolicy` has been applied.
+	///
+	/// `hyper_thread` must have a queue.
+	#[inline(always)]
+	pub fn overflow_counters(&self, hyper_thread: HyperThread) -> &OverflowCounters
+	{
+		self.0.get_unchecked_safe(hyper_thread).overflow_counters()
+	}
+	
+	/// Number of messages which had expired by the time they were dequeued, and so were dropped in place without calling their message handler, since the queue was created.
+	///
+	/// Unlike `DequeueOutcome.messages_expired`, this is not reset by each dequeue; it is updated with relaxed memory ordering and so is suitable for telemetry but not for synchronization.
+	///
+	/// `hyper_thread` must have a queue.
+	#[inline(always)]
+	pub fn messages_expired(&self, hyper_thread: HyperThread) -> u64
+	{
+		self.0.get_unchecked_safe(hyper_thread).messages_expired()
+	}
+	
+	/// Wakes the subscriber for `hyper_thread` if it is parked in `Subscriber::receive_and_handle_messages_blocking()`.
+	///
+	/// Useful after signalling termination.
+	///
+	/// `hyper_thread` must have a queue.
+	#[inline(always)]
+	pub fn wake_subscriber(&self, hyper_thread: HyperThread)
+	{
+		self.0.get_unchecked_safe(hyper_thread).wake_subscriber()
+	}
 }
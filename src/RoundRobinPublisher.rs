@@ -3,6 +3,10 @@
 
 
 /// A round-robin publisher.
+///
+/// Publishes to each of a fixed sequence (`hyper_threads_to_publish_to`) of `HyperThread`s in turn; a hyper thread may appear more than once in the sequence to receive proportionally more messages.
+///
+/// Not thread safe; use `SharedRoundRobinPublisher` to share one distribution policy between several publishing threads.
 #[derive(Debug)]
 pub struct RoundRobinPublisher<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>
 {
@@ -16,7 +20,7 @@ impl<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, Deque
 	#[inline(always)]
 	fn new(queues: &Queues<MessageHandlerArguments, DequeuedMessageProcessingError>, hyper_threads_to_publish_to: Box<[HyperThread]>) -> Self
 	{
-		debug_assert_ne!(hyper_threads_to_publish_to.len(), 0);
+		queues.assert_hyper_threads_to_publish_to_have_queues(&hyper_threads_to_publish_to);
 		let default_hyper_thread = hyper_threads_to_publish_to.get_unchecked_value_safe(0);
 		
 		Self
@@ -32,13 +36,37 @@ impl<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, Deque
 	/// Returns the actual hyper thread published to.
 	#[inline(always)]
 	pub fn publish(&self, construct_message_arguments: M::ConstructMessageArguments)-> HyperThread
+	{
+		self.publisher.publish(self.next_hyper_thread(), construct_message_arguments)
+	}
+	
+	/// A publisher publishes to a specific hyper thread without waiting for space in the queue.
+	///
+	/// The round-robin position is advanced even if the queue is full, so a retry will be offered to the next hyper thread.
+	///
+	/// Returns the actual hyper thread published to, or, if its queue is full, a `PublishError::QueueFull` which hands back the unconsumed `construct_message_arguments`.
+	#[inline(always)]
+	pub fn try_publish(&self, construct_message_arguments: M::ConstructMessageArguments) -> Result<HyperThread, PublishError<M::ConstructMessageArguments>>
+	{
+		self.publisher.try_publish(self.next_hyper_thread(), construct_message_arguments)
+	}
+	
+	#[inline(always)]
+	fn next_hyper_thread(&self) -> HyperThread
 	{
 		let next_hyper_thread_to_publish_to_index = self.next_hyper_thread_to_publish_to_index.get();
 		let next_hyper_thread = self.hyper_threads_to_publish_to.get_unchecked_value_safe(next_hyper_thread_to_publish_to_index);
-		if next_hyper_thread_to_publish_to_index == self.hyper_threads_to_publish_to.len()
+		
+		let following_hyper_thread_to_publish_to_index = next_hyper_thread_to_publish_to_index + 1;
+		self.next_hyper_thread_to_publish_to_index.set(if following_hyper_thread_to_publish_to_index == self.hyper_threads_to_publish_to.len()
 		{
-			self.next_hyper_thread_to_publish_to_index.set(0)
+			0
 		}
-		self.publisher.publish(next_hyper_thread, construct_message_arguments)
+		else
+		{
+			following_hyper_thread_to_publish_to_index
+		});
+		
+		next_hyper_thread
 	}
 }
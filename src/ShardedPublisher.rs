@@ -0,0 +1,139 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A key-affinity (sharded) publisher, which always publishes messages with the same key to the same `HyperThread`.
+///
+/// Keys are mapped to hyper threads using consistent hashing: each hyper thread is placed at `virtual_nodes_per_hyper_thread` points on a ring of hashes and a key is published to the hyper thread at the first point at or after the hash of the key.
+/// Consequently, adding or removing a hyper thread only moves the keys of (approximately) `1 / number of hyper threads` to a different hyper thread.
+///
+/// As all messages for a key go to the same queue, they are handled in the order they were published by any one publishing thread.
+/// This does not hold across `add_hyper_thread()` or `remove_hyper_thread()`: see their documentation.
+#[derive(Debug)]
+pub struct ShardedPublisher<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, K: Hash, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>
+{
+	queues: Queues<MessageHandlerArguments, DequeuedMessageProcessingError>,
+	publisher: Publisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>,
+	virtual_nodes_per_hyper_thread: NonZeroUsize,
+	hyper_threads_to_publish_to: Vec<HyperThread>,
+	ring: Box<[(u64, HyperThread)]>,
+	marker: PhantomData<K>,
+}
+
+impl<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, K: Hash, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> ShardedPublisher<M, K, MessageHandlerArguments, DequeuedMessageProcessingError>
+{
+	#[inline(always)]
+	fn new(queues: &Queues<MessageHandlerArguments, DequeuedMessageProcessingError>, hyper_threads_to_publish_to: Box<[HyperThread]>, virtual_nodes_per_hyper_thread: NonZeroUsize) -> Self
+	{
+		queues.assert_hyper_threads_to_publish_to_have_queues(&hyper_threads_to_publish_to);
+		let default_hyper_thread = hyper_threads_to_publish_to.get_unchecked_value_safe(0);
+		let hyper_threads_to_publish_to = hyper_threads_to_publish_to.into_vec();
+		
+		Self
+		{
+			queues: queues.clone(),
+			publisher: Publisher::new(queues, default_hyper_thread),
+			virtual_nodes_per_hyper_thread,
+			ring: Self::ring(&hyper_threads_to_publish_to, virtual_nodes_per_hyper_thread),
+			hyper_threads_to_publish_to,
+			marker: PhantomData,
+		}
+	}
+	
+	/// Publishes to the hyper thread for `key`.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish(&self, key: &K, construct_message_arguments: M::ConstructMessageArguments) -> HyperThread
+	{
+		self.publisher.publish(self.hyper_thread_for_key(key), construct_message_arguments)
+	}
+	
+	/// Publishes to the hyper thread for `key` without waiting for space in the queue.
+	///
+	/// Returns the actual hyper thread published to, or, if its queue is full, a `PublishError::QueueFull` which hands back the unconsumed `construct_message_arguments`.
+	#[inline(always)]
+	pub fn try_publish(&self, key: &K, construct_message_arguments: M::ConstructMessageArguments) -> Result<HyperThread, PublishError<M::ConstructMessageArguments>>
+	{
+		self.publisher.try_publish(self.hyper_thread_for_key(key), construct_message_arguments)
+	}
+	
+	/// The hyper thread that messages for `key` are published to.
+	#[inline(always)]
+	pub fn hyper_thread_for_key(&self, key: &K) -> HyperThread
+	{
+		let hash = Self::hash(key);
+		let index = match self.ring.binary_search_by(|&(point, _)| point.cmp(&hash))
+		{
+			Ok(index) => index,
+			
+			Err(index) => if index == self.ring.len()
+			{
+				0
+			}
+			else
+			{
+				index
+			},
+		};
+		self.ring.get_unchecked_safe(index).1
+	}
+	
+	/// Adds a hyper thread; only keys which now map to its points on the ring move to it.
+	///
+	/// Messages for a key which moves that are published afterwards may be handled before those published beforehand which are still in the queue of the hyper thread the key used to map to.
+	/// To keep messages for each key in order, stop publishing and let the queues of the hyper threads to publish to drain before calling this.
+	///
+	/// Does nothing if the hyper thread has already been added.
+	/// Panics if the hyper thread does not have a queue.
+	#[inline(always)]
+	pub fn add_hyper_thread(&mut self, hyper_thread: HyperThread)
+	{
+		assert!(self.queues.has_queue(hyper_thread), "Hyper thread `{:?}` to publish to does not have a queue", hyper_thread);
+		
+		if !self.hyper_threads_to_publish_to.contains(&hyper_thread)
+		{
+			self.hyper_threads_to_publish_to.push(hyper_thread);
+			self.ring = Self::ring(&self.hyper_threads_to_publish_to, self.virtual_nodes_per_hyper_thread)
+		}
+	}
+	
+	/// Removes a hyper thread; only keys which mapped to it move, to the hyper threads at the following points on the ring.
+	///
+	/// Messages for a key which moves that are published afterwards may be handled before those published beforehand which are still in the removed hyper thread's queue.
+	/// To keep messages for each key in order, stop publishing and let the removed hyper thread's queue drain before calling this.
+	///
+	/// Panics if this would remove the last hyper thread; nothing is removed.
+	#[inline(always)]
+	pub fn remove_hyper_thread(&mut self, hyper_thread: HyperThread)
+	{
+		assert!(self.hyper_threads_to_publish_to.iter().any(|&existing_hyper_thread| existing_hyper_thread != hyper_thread), "Can not remove the last hyper thread");
+		
+		self.hyper_threads_to_publish_to.retain(|&existing_hyper_thread| existing_hyper_thread != hyper_thread);
+		self.ring = Self::ring(&self.hyper_threads_to_publish_to, self.virtual_nodes_per_hyper_thread)
+	}
+	
+	#[inline(always)]
+	fn ring(hyper_threads_to_publish_to: &[HyperThread], virtual_nodes_per_hyper_thread: NonZeroUsize) -> Box<[(u64, HyperThread)]>
+	{
+		let mut ring = Vec::with_capacity(hyper_threads_to_publish_to.len() * virtual_nodes_per_hyper_thread.get());
+		for &hyper_thread in hyper_threads_to_publish_to
+		{
+			for virtual_node in 0 .. virtual_nodes_per_hyper_thread.get()
+			{
+				ring.push((Self::hash(&(hyper_thread, virtual_node)), hyper_thread))
+			}
+		}
+		ring.sort_unstable();
+		ring.into_boxed_slice()
+	}
+	
+	/// `DefaultHasher::new()` always uses the same keys, so hashes are stable for the lifetime of the process.
+	#[inline(always)]
+	fn hash(value: &impl Hash) -> u64
+	{
+		let mut hasher = DefaultHasher::new();
+		value.hash(&mut hasher);
+		hasher.finish()
+	}
+}
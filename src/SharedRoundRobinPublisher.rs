@@ -0,0 +1,71 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A round-robin publisher which can be shared by several publishing threads, eg in an `Arc`.
+///
+/// As for `RoundRobinPublisher`, but the position in the sequence (`hyper_threads_to_publish_to`) is an atomic cursor, so all publishing threads together follow one distribution policy.
+#[derive(Debug)]
+pub struct SharedRoundRobinPublisher<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>
+{
+	publisher: Publisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>,
+	hyper_threads_to_publish_to: Box<[HyperThread]>,
+	cursor: AtomicUsize,
+}
+
+/// Queues are safe to publish to from many threads at once and are kept alive by the `Publisher`.
+///
+/// Messages are constructed on the publishing thread from `M::ConstructMessageArguments` moved from it, then handled on a subscriber's thread, so both must be `Send`.
+unsafe impl<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> Send for SharedRoundRobinPublisher<M, MessageHandlerArguments, DequeuedMessageProcessingError> where M: Send, M::ConstructMessageArguments: Send
+{
+}
+
+/// As for `Send`.
+unsafe impl<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> Sync for SharedRoundRobinPublisher<M, MessageHandlerArguments, DequeuedMessageProcessingError> where M: Send, M::ConstructMessageArguments: Send
+{
+}
+
+impl<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> SharedRoundRobinPublisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>
+{
+	#[inline(always)]
+	fn new(queues: &Queues<MessageHandlerArguments, DequeuedMessageProcessingError>, hyper_threads_to_publish_to: Box<[HyperThread]>) -> Self
+	{
+		queues.assert_hyper_threads_to_publish_to_have_queues(&hyper_threads_to_publish_to);
+		let default_hyper_thread = hyper_threads_to_publish_to.get_unchecked_value_safe(0);
+		
+		Self
+		{
+			publisher: Publisher::new(queues, default_hyper_thread),
+			hyper_threads_to_publish_to,
+			cursor: AtomicUsize::new(0),
+		}
+	}
+	
+	/// Publishes to the next hyper thread in the sequence.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish(&self, construct_message_arguments: M::ConstructMessageArguments) -> HyperThread
+	{
+		self.publisher.publish(self.next_hyper_thread(), construct_message_arguments)
+	}
+	
+	/// Publishes to the next hyper thread in the sequence without waiting for space in the queue.
+	///
+	/// See `RoundRobinPublisher::try_publish()` for what happens when the queue is full.
+	///
+	/// Returns the actual hyper thread published to, or, if its queue is full, a `PublishError::QueueFull` which hands back the unconsumed `construct_message_arguments`.
+	#[inline(always)]
+	pub fn try_publish(&self, construct_message_arguments: M::ConstructMessageArguments) -> Result<HyperThread, PublishError<M::ConstructMessageArguments>>
+	{
+		self.publisher.try_publish(self.next_hyper_thread(), construct_message_arguments)
+	}
+	
+	/// The cursor wraps at `usize::MAX`, which causes a single discontinuity in the sequence unless its length is a power of two.
+	#[inline(always)]
+	fn next_hyper_thread(&self) -> HyperThread
+	{
+		let cursor = self.cursor.fetch_add(1, Relaxed);
+		self.hyper_threads_to_publish_to.get_unchecked_value_safe(cursor % self.hyper_threads_to_publish_to.len())
+	}
+}
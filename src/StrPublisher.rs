@@ -0,0 +1,72 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A publisher for one type of variably sized message, consisting of a header, `Header`, and a string payload.
+///
+/// The header and payload are written directly into the queue's magic ring buffer; there is no heap allocation.
+///
+/// See `VariablySizedPublisher`.
+#[derive(Debug)]
+pub struct StrPublisher<Header: 'static + VariablySizedStrMessage<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>
+{
+	publisher: VariablySizedPublisher<StrHeader<Header>, MessageHandlerArguments, DequeuedMessageProcessingError>,
+}
+
+impl<Header: 'static + VariablySizedStrMessage<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> StrPublisher<Header, MessageHandlerArguments, DequeuedMessageProcessingError>
+{
+	#[inline(always)]
+	fn new(queues: &Queues<MessageHandlerArguments, DequeuedMessageProcessingError>, default_hyper_thread: HyperThread) -> Self
+	{
+		Self
+		{
+			publisher: VariablySizedPublisher::new(queues, default_hyper_thread),
+		}
+	}
+	
+	/// Publishes `header` followed by a copy of `payload` to a specific hyper thread.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish(&self, hyper_thread: HyperThread, header: Header, payload: &str) -> HyperThread
+	{
+		self.publish_with_priority(hyper_thread, Priority::Highest, header, payload)
+	}
+	
+	/// Publishes `header` followed by a copy of `payload` to the priority lane for `priority` of a specific hyper thread.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	/// If the queue has fewer priority lanes than `priority` needs, publishes to its lowest priority lane.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish_with_priority(&self, hyper_thread: HyperThread, priority: Priority, header: Header, payload: &str) -> HyperThread
+	{
+		self.publisher.publish_with_priority(hyper_thread, priority, StrHeader(header), payload.as_bytes())
+	}
+	
+	/// Publishes `header` followed by a copy of `payload` to a specific hyper thread without waiting for space in the queue.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	///
+	/// Returns the actual hyper thread published to, or, if its queue is full, a `PublishError::QueueFull` which hands back the unconsumed `header`.
+	#[inline(always)]
+	pub fn try_publish(&self, hyper_thread: HyperThread, header: Header, payload: &str) -> Result<HyperThread, PublishError<Header>>
+	{
+		self.try_publish_with_priority(hyper_thread, Priority::Highest, header, payload)
+	}
+	
+	/// Publishes `header` followed by a copy of `payload` to the priority lane for `priority` of a specific hyper thread without waiting for space in the queue.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	/// If the queue has fewer priority lanes than `priority` needs, publishes to its lowest priority lane.
+	///
+	/// Returns the actual hyper thread published to, or, if its priority lane is full, a `PublishError::QueueFull` which hands back the unconsumed `header`.
+	#[inline(always)]
+	pub fn try_publish_with_priority(&self, hyper_thread: HyperThread, priority: Priority, header: Header, payload: &str) -> Result<HyperThread, PublishError<Header>>
+	{
+		self.publisher.try_publish_with_priority(hyper_thread, priority, StrHeader(header), payload.as_bytes()).map_err(|publish_error| publish_error.map_construct_message_arguments(|StrHeader(header)| header))
+	}
+}
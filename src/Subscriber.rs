@@ -28,17 +28,41 @@ impl<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> Subs
 	#[inline(always)]
 	fn new(queues: &Queues<MessageHandlerArguments, DequeuedMessageProcessingError>, for_hyper_thread: HyperThread) -> Self
 	{
+		let queue = queues.0.get_unchecked_safe(for_hyper_thread);
+		queue.register_subscriber_thread();
+		
 		Self
 		{
 			_queues_drop_reference: queues.clone(),
-			queue: queues.0.get_unchecked_safe(for_hyper_thread),
+			queue,
 			#[cfg(debug_assertions)] for_hyper_thread,
 		}
 	}
 	
-	/// Receives and handles messages; short-circuits if `self.terminate` becomes true or a message handler returns an error `DequeuedMessageProcessingError`.
+	/// Receives and handles messages; short-circuits if `self.terminate` becomes true, `dequeue_budget` is exhausted or a message handler returns an error `DequeuedMessageProcessingError` which `dequeued_message_processing_error_policy` does not continue from.
+	///
+	/// Use `DequeueBudget::Unlimited` to receive and handle messages until the queue is empty.
+	/// Use `DequeuedMessageProcessingErrorPolicy::StopAndLeaveRemaining` to return the first error a message handler returns; it is returned with the outcome of dequeuing so far as a `DequeueFailed`.
+	#[inline(always)]
+	pub fn receive_and_handle_messages(&self, terminate: &Arc<impl Terminate>, message_handler_arguments: &MessageHandlerArguments, dequeue_budget: &DequeueBudget, dequeued_message_processing_error_policy: &mut DequeuedMessageProcessingErrorPolicy<DequeuedMessageProcessingError>) -> Result<DequeueOutcome, DequeueFailed<DequeuedMessageProcessingError>>
+	{
+		#[cfg(debug_assertions)]
+		{
+			debug_assert_eq!(self.for_hyper_thread, HyperThread::current().1, "Must only be accessed by one specific HyperThread")
+		}
+		
+		let queue = unsafe { &*self.queue };
+		queue.dequeue(terminate, message_handler_arguments, dequeue_budget, dequeued_message_processing_error_policy)
+	}
+	
+	/// Receives and handles messages, first parking this thread on a futex if there are no messages to receive.
+	///
+	/// The thread is woken by the first message published after the queue became empty, when `timeout` (if any) elapses, by `Queues::wake_subscriber()` (eg to observe `terminate`) or spuriously.
+	/// Publishers do not make a system call to wake this thread unless it is parked.
+	///
+	/// Otherwise behaves as `receive_and_handle_messages()`.
 	#[inline(always)]
-	pub fn receive_and_handle_messages(&self, terminate: &Arc<impl Terminate>, message_handler_arguments: &MessageHandlerArguments) -> Result<(), DequeuedMessageProcessingError>
+	pub fn receive_and_handle_messages_blocking(&self, terminate: &Arc<impl Terminate>, message_handler_arguments: &MessageHandlerArguments, dequeue_budget: &DequeueBudget, dequeued_message_processing_error_policy: &mut DequeuedMessageProcessingErrorPolicy<DequeuedMessageProcessingError>, timeout: Option<Duration>) -> Result<DequeueOutcome, DequeueFailed<DequeuedMessageProcessingError>>
 	{
 		#[cfg(debug_assertions)]
 		{
@@ -46,6 +70,45 @@ impl<MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> Subs
 		}
 		
 		let queue = unsafe { &*self.queue };
-		queue.dequeue(terminate, message_handler_arguments)
+		queue.park_subscriber(timeout);
+		queue.dequeue(terminate, message_handler_arguments, dequeue_budget, dequeued_message_processing_error_policy)
+	}
+	
+	/// Takes the panics of message handlers caught, if `QueueSettings.catch_message_handler_panics` was `true`, since they were last taken.
+	///
+	/// Each is counted in `DequeueOutcome.message_handlers_panicked` when it happens.
+	/// Does not allocate unless a message handler panicked.
+	#[inline(always)]
+	pub fn take_message_handler_panics(&self) -> Vec<HandlerPanicked>
+	{
+		self.queue().take_message_handler_panics()
+	}
+	
+	/// When the next message which was dequeued before it was due (and so moved to the timer wheel) is due, if any.
+	///
+	/// The eventfd is not signalled when such a message becomes due; arm a timer (eg a timerfd) for this time and then call `receive_and_handle_messages()`.
+	/// This is also reported in `DequeueOutcome.next_scheduled_message_due_at`.
+	#[inline(always)]
+	pub fn next_scheduled_message_due_at(&self) -> Option<MonotonicTime>
+	{
+		self.queue().next_scheduled_message_due_at()
+	}
+	
+	/// The queue's eventfd, if `QueueSettings.event_file_descriptor` was `true`.
+	///
+	/// Register this for read readiness (`EPOLLIN`) with an epoll reactor; when it is readable, call `receive_and_handle_messages()`.
+	/// Do not read from or write to it.
+	/// It is not made readable when a message in the timer wheel becomes due; see `next_scheduled_message_due_at()`.
+	#[inline(always)]
+	pub fn as_raw_fd(&self) -> Option<RawFd>
+	{
+		let queue = unsafe { &*self.queue };
+		queue.event_file_descriptor()
+	}
+	
+	#[inline(always)]
+	fn queue(&self) -> &Queue<MessageHandlerArguments, DequeuedMessageProcessingError>
+	{
+		unsafe { &*self.queue }
 	}
 }
@@ -0,0 +1,82 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// Parks a subscriber on a futex until a publisher makes its queue non-empty.
+///
+/// Publishers only inspect the futex word when a queue transitions from empty to non-empty, and only make a system call if the subscriber is actually parked; when the subscriber is awake, publishing never makes a system call.
+#[derive(Debug)]
+struct SubscriberWakeUp
+{
+	futex: AtomicU32,
+}
+
+impl Default for SubscriberWakeUp
+{
+	#[inline(always)]
+	fn default() -> Self
+	{
+		Self
+		{
+			futex: AtomicU32::new(Self::Awake),
+		}
+	}
+}
+
+impl SubscriberWakeUp
+{
+	const Awake: u32 = 0;
+	
+	const Parked: u32 = 1;
+	
+	/// Parks the calling thread until woken, `timeout` elapses, or a spurious wake up occurs.
+	///
+	/// Does not park if `has_pending_messages` is `true` once the futex word has been set to parked; this check is what prevents a lost wake up.
+	#[inline(always)]
+	fn park(&self, has_pending_messages: impl FnOnce() -> bool, timeout: Option<Duration>)
+	{
+		self.futex.store(Self::Parked, SeqCst);
+		
+		if !has_pending_messages()
+		{
+			let timeout = timeout.map(|timeout| timespec { tv_sec: timeout.as_secs() as time_t, tv_nsec: timeout.subsec_nanos() as c_long });
+			let timeout_pointer = match timeout
+			{
+				None => null(),
+				
+				Some(ref timeout) => timeout as *const timespec,
+			};
+			
+			let result = unsafe { syscall(SYS_futex, self.futex_pointer(), FUTEX_WAIT_PRIVATE, Self::Parked, timeout_pointer) };
+			if cfg!(debug_assertions)
+			{
+				if result == -1
+				{
+					let errno = unsafe { *__errno_location() };
+					debug_assert!(errno == EAGAIN || errno == EINTR || errno == ETIMEDOUT, "Unexpected error from futex wait `{}`", errno)
+				}
+			}
+		}
+		
+		self.futex.store(Self::Awake, SeqCst);
+	}
+	
+	/// Wakes the subscriber if, and only if, it is parked.
+	#[inline(always)]
+	fn wake_if_parked(&self)
+	{
+		if self.futex.load(SeqCst) == Self::Parked
+		{
+			if self.futex.swap(Self::Awake, SeqCst) == Self::Parked
+			{
+				unsafe { syscall(SYS_futex, self.futex_pointer(), FUTEX_WAKE_PRIVATE, 1) };
+			}
+		}
+	}
+	
+	#[inline(always)]
+	fn futex_pointer(&self) -> *const u32
+	{
+		&self.futex as *const AtomicU32 as *const u32
+	}
+}
@@ -0,0 +1,55 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// Holds the `Waker` of an `AsyncSubscriber` waiting for its queue to become non-empty.
+///
+/// Publishers only inspect this when a queue transitions from empty to non-empty, and only take the lock if a waker is registered.
+#[derive(Default, Debug)]
+struct SubscriberWaker
+{
+	registered: AtomicBool,
+	waker: Mutex<Option<Waker>>,
+}
+
+impl SubscriberWaker
+{
+	/// Registers (or replaces) the waker to wake when the queue becomes non-empty.
+	///
+	/// The caller must check for pending messages after registering to avoid a lost wake up.
+	#[inline(always)]
+	fn register(&self, waker: &Waker)
+	{
+		let mut slot = self.waker.lock().unwrap();
+		let already_registered = match *slot
+		{
+			None => false,
+			
+			Some(ref registered_waker) => registered_waker.will_wake(waker),
+		};
+		if !already_registered
+		{
+			*slot = Some(waker.clone())
+		}
+		drop(slot);
+		
+		self.registered.store(true, SeqCst);
+	}
+	
+	/// Wakes, and unregisters, the registered waker, if any.
+	#[inline(always)]
+	fn wake_if_registered(&self)
+	{
+		if self.registered.load(SeqCst)
+		{
+			if self.registered.swap(false, SeqCst)
+			{
+				let waker = self.waker.lock().unwrap().take();
+				if let Some(waker) = waker
+				{
+					waker.wake()
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,247 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A hierarchical timer wheel holding messages which were dequeued before they were due.
+///
+/// Time is divided into ticks of `2^TickLogarithmBase2` nanoseconds (a little over a millisecond).
+/// There are `NumberOfLevels` levels each of `2^SlotsPerLevelLogarithmBase2` slots; a slot at level `L` spans `2^(SlotsPerLevelLogarithmBase2 * L)` ticks.
+/// As time advances, the messages in a slot at a higher level are cascaded down to lower levels, and those in a slot at the lowest level are due.
+/// Messages due further in the future than the highest level spans wait in `far_future` until they come into range.
+///
+/// A message is never due before its due time: its due time is rounded up to a whole tick, and a tick is only processed once it has wholly passed.
+///
+/// Only ever used by the single consumer of a `Queue`.
+#[derive(Debug)]
+struct TimerWheel
+{
+	current_tick: u64,
+	slots: Box<[Vec<ScheduledMessage>]>,
+	far_future: Vec<ScheduledMessage>,
+	due: Vec<ScheduledMessage>,
+	number_of_scheduled_messages: usize,
+}
+
+impl Default for TimerWheel
+{
+	#[inline(always)]
+	fn default() -> Self
+	{
+		Self
+		{
+			current_tick: Self::tick_not_after(MonotonicTime::now()) + 1,
+			slots: (0 .. (Self::NumberOfLevels * Self::SlotsPerLevel)).map(|_| Vec::new()).collect(),
+			far_future: Vec::new(),
+			due: Vec::new(),
+			number_of_scheduled_messages: 0,
+		}
+	}
+}
+
+impl TimerWheel
+{
+	const TickLogarithmBase2: u32 = 20;
+	
+	const SlotsPerLevelLogarithmBase2: u32 = 6;
+	
+	const SlotsPerLevel: usize = 1 << Self::SlotsPerLevelLogarithmBase2;
+	
+	const SlotMask: u64 = (Self::SlotsPerLevel as u64) - 1;
+	
+	const NumberOfLevels: usize = 4;
+	
+	const TicksSpannedByAllLevels: u64 = 1 << (Self::SlotsPerLevelLogarithmBase2 * (Self::NumberOfLevels as u32));
+	
+	/// Is there any message waiting?
+	#[inline(always)]
+	fn is_empty(&self) -> bool
+	{
+		self.number_of_scheduled_messages == 0
+	}
+	
+	/// `scheduled_message` must not yet be due at `now`, and `now` must be no earlier than that last passed to `next_due_message()`.
+	///
+	/// If there are no messages waiting, time is first advanced to `now`, as `next_due_message()` is not called whilst the timer wheel is empty and so `current_tick` is stale.
+	#[inline(always)]
+	fn schedule(&mut self, now: MonotonicTime, scheduled_message: ScheduledMessage)
+	{
+		if self.is_empty()
+		{
+			self.current_tick = Self::tick_not_after(now) + 1
+		}
+		
+		debug_assert!(Self::tick_not_before(scheduled_message.due_time()) >= self.current_tick, "scheduled_message is already due");
+		
+		self.insert(scheduled_message);
+		self.number_of_scheduled_messages += 1
+	}
+	
+	/// Removes the next message due at or before `now`, advancing time as needed.
+	#[inline(always)]
+	fn next_due_message(&mut self, now: MonotonicTime) -> Option<ScheduledMessage>
+	{
+		let now_tick = Self::tick_not_after(now);
+		
+		loop
+		{
+			if let Some(scheduled_message) = self.due.pop()
+			{
+				self.number_of_scheduled_messages -= 1;
+				return Some(scheduled_message)
+			}
+			
+			if self.current_tick > now_tick
+			{
+				return None
+			}
+			
+			if self.is_empty()
+			{
+				self.current_tick = now_tick + 1;
+				return None
+			}
+			
+			self.cascade();
+			
+			let slot_index = Self::slot_index(0, self.current_tick);
+			swap(&mut self.due, &mut self.slots[slot_index]);
+			self.due.reverse();
+			
+			self.current_tick += 1
+		}
+	}
+	
+	/// The time at or after which `next_due_message()` must next be called for the messages waiting to be removed once due, or `None` if there are none.
+	///
+	/// This may be earlier than the due time of any message, as messages in the slots of higher levels (and `far_future`) are only placed precisely once cascaded.
+	/// If a message is already due, it is in the past.
+	#[inline(always)]
+	fn next_due_time(&self) -> Option<MonotonicTime>
+	{
+		if self.is_empty()
+		{
+			return None
+		}
+		
+		if !self.due.is_empty()
+		{
+			return Some(Self::start_of_tick(self.current_tick - 1))
+		}
+		
+		let next_tick_with_messages_in_lowest_level = (self.current_tick .. (self.current_tick + Self::SlotsPerLevel as u64)).find(|&tick| !self.slots[Self::slot_index(0, tick)].is_empty());
+		
+		let has_messages_in_higher_levels = !self.far_future.is_empty() || self.slots[Self::SlotsPerLevel .. ].iter().any(|slot| !slot.is_empty());
+		let next_tick_to_cascade = if has_messages_in_higher_levels
+		{
+			let ticks_spanned_by_slot_at_level_1 = Self::ticks_spanned_by_slot_at_level(1);
+			Some((self.current_tick + ticks_spanned_by_slot_at_level_1 - 1) & !(ticks_spanned_by_slot_at_level_1 - 1))
+		}
+		else
+		{
+			None
+		};
+		
+		let next_tick = match (next_tick_with_messages_in_lowest_level, next_tick_to_cascade)
+		{
+			(Some(left), Some(right)) => min(left, right),
+			
+			(Some(tick), None) | (None, Some(tick)) => tick,
+			
+			(None, None) => unreachable!("number_of_scheduled_messages is wrong"),
+		};
+		Some(Self::start_of_tick(next_tick))
+	}
+	
+	/// Removes every message, whether due or not.
+	#[inline(always)]
+	fn drain(&mut self) -> impl Iterator<Item=ScheduledMessage> + '_
+	{
+		self.number_of_scheduled_messages = 0;
+		self.due.drain(..).chain(self.far_future.drain(..)).chain(self.slots.iter_mut().flat_map(|slot| slot.drain(..)))
+	}
+	
+	/// Moves messages down from the slots of higher levels (and `far_future`) which start at `current_tick`; highest first, so messages can fall more than one level.
+	#[inline(always)]
+	fn cascade(&mut self)
+	{
+		if self.current_tick & (Self::TicksSpannedByAllLevels - 1) == 0
+		{
+			for scheduled_message in take(&mut self.far_future)
+			{
+				self.insert(scheduled_message)
+			}
+		}
+		
+		for level in (1 .. Self::NumberOfLevels).rev()
+		{
+			let ticks_spanned_by_slot_at_level = Self::ticks_spanned_by_slot_at_level(level);
+			if self.current_tick & (ticks_spanned_by_slot_at_level - 1) == 0
+			{
+				let slot_index = Self::slot_index(level, self.current_tick);
+				for scheduled_message in take(&mut self.slots[slot_index])
+				{
+					self.insert(scheduled_message)
+				}
+			}
+		}
+	}
+	
+	#[inline(always)]
+	fn insert(&mut self, scheduled_message: ScheduledMessage)
+	{
+		let due_tick = Self::tick_not_before(scheduled_message.due_time());
+		let ticks_until_due = due_tick.saturating_sub(self.current_tick);
+		
+		for level in 0 .. Self::NumberOfLevels
+		{
+			if ticks_until_due < Self::ticks_spanned_by_slot_at_level(level + 1)
+			{
+				self.slots[Self::slot_index(level, due_tick)].push(scheduled_message);
+				return
+			}
+		}
+		
+		self.far_future.push(scheduled_message)
+	}
+	
+	#[inline(always)]
+	fn slot_index(level: usize, tick: u64) -> usize
+	{
+		let slot_index_within_level = ((tick >> (Self::SlotsPerLevelLogarithmBase2 * (level as u32))) & Self::SlotMask) as usize;
+		level * Self::SlotsPerLevel + slot_index_within_level
+	}
+	
+	#[inline(always)]
+	fn ticks_spanned_by_slot_at_level(level: usize) -> u64
+	{
+		1 << (Self::SlotsPerLevelLogarithmBase2 * (level as u32))
+	}
+	
+	/// The earliest time at which `next_due_message()` processes `tick`.
+	#[inline(always)]
+	fn start_of_tick(tick: u64) -> MonotonicTime
+	{
+		MonotonicTime::from_nanoseconds(tick << Self::TickLogarithmBase2)
+	}
+	
+	#[inline(always)]
+	fn tick_not_after(time: MonotonicTime) -> u64
+	{
+		time.nanoseconds() >> Self::TickLogarithmBase2
+	}
+	
+	#[inline(always)]
+	fn tick_not_before(time: MonotonicTime) -> u64
+	{
+		let nanoseconds = time.nanoseconds();
+		let tick = nanoseconds >> Self::TickLogarithmBase2;
+		if nanoseconds & ((1 << Self::TickLogarithmBase2) - 1) == 0
+		{
+			tick
+		}
+		else
+		{
+			tick + 1
+		}
+	}
+}
@@ -0,0 +1,43 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// Implement this for a trait object, eg `dyn MyTrait`, so that any value of a type implementing `MyTrait` can be published to a queue without registering each type.
+///
+/// For example:-
+///
+/// ```ignore
+/// unsafe impl TraitObjectMessage for dyn MyTrait
+/// {
+/// 	type MessageHandlerArguments = MyMessageHandlerArguments;
+///
+/// 	type DequeuedMessageProcessingError = MyDequeuedMessageProcessingError;
+///
+/// 	fn handle_message(&mut self, message_handler_arguments: &Self::MessageHandlerArguments) -> Result<(), Self::DequeuedMessageProcessingError>
+/// 	{
+/// 		self.my_trait_method(message_handler_arguments)
+/// 	}
+/// }
+/// ```
+///
+/// The value is moved into the queue's magic ring buffer preceded by its virtual method table pointer; `handle_message()` and dropping the value are then dispatched through the virtual method table.
+///
+/// Publish using a `TraitObjectPublisher`.
+///
+/// # Safety
+///
+/// Must only be implemented for a trait object type, `dyn MyTrait` (including with auto traits, eg `dyn MyTrait + Send`).
+/// Its pointer metadata is assumed to be a virtual method table pointer; implementing this for any other unsized type, eg `[u8]` or `str`, whose metadata is a length, or for a sized type, is undefined behaviour when a value is published.
+pub unsafe trait TraitObjectMessage: 'static
+{
+	/// Message handler arguments.
+	type MessageHandlerArguments;
+	
+	/// Error that can happen when processing a dequeued message.
+	type DequeuedMessageProcessingError: error::Error;
+	
+	/// Handle a message (used by a receiving thread).
+	///
+	/// Messages are automatically dropped after this has been called.
+	fn handle_message(&mut self, message_handler_arguments: &Self::MessageHandlerArguments) -> Result<(), Self::DequeuedMessageProcessingError>;
+}
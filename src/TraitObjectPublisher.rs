@@ -0,0 +1,83 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A publisher for values of any type implementing a trait, `Trait`, for which `TraitObjectMessage` is implemented (eg `dyn MyTrait`).
+///
+/// Values are moved into the queue's magic ring buffer preceded by their virtual method table pointer; there is no heap allocation.
+///
+/// See `Publisher` for why this holds a `*const Queue`, and for what happens when publishing to a queue whose `MessageHandlers` do not have `Trait` registered.
+#[derive(Debug)]
+pub struct TraitObjectPublisher<Trait: ?Sized + TraitObjectMessage<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>
+{
+	_queues_drop_reference: Queues<MessageHandlerArguments, DequeuedMessageProcessingError>,
+	queues_mapped: PerBitSetAwareData<HyperThread, (*const Queue<MessageHandlerArguments, DequeuedMessageProcessingError>, Option<CompressedTypeIdentifier>)>,
+	default_hyper_thread: HyperThread,
+	marker: PhantomData<Box<Trait>>,
+}
+
+impl<Trait: ?Sized + TraitObjectMessage<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> TraitObjectPublisher<Trait, MessageHandlerArguments, DequeuedMessageProcessingError>
+{
+	#[inline(always)]
+	fn new(queues: &Queues<MessageHandlerArguments, DequeuedMessageProcessingError>, default_hyper_thread: HyperThread) -> Self
+	{
+		Self
+		{
+			_queues_drop_reference: queues.clone(),
+			queues_mapped: queues.0.map_ref(|_hyper_thread, queue| (queue as *const _, queue.fixed_sized_message_body_compressed_type_identifier::<TraitObjectMessageBody<Trait, ()>>())),
+			default_hyper_thread,
+			marker: PhantomData,
+		}
+	}
+	
+	/// A publisher publishes to a specific hyper thread, moving `value` into the queue.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish<Value: 'static + Unsize<Trait>>(&self, hyper_thread: HyperThread, value: Value) -> HyperThread
+	{
+		self.publish_with_priority(hyper_thread, Priority::Highest, value)
+	}
+	
+	/// A publisher publishes to the priority lane for `priority` of a specific hyper thread, moving `value` into the queue.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	/// If the queue has fewer priority lanes than `priority` needs, publishes to its lowest priority lane.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish_with_priority<Value: 'static + Unsize<Trait>>(&self, hyper_thread: HyperThread, priority: Priority, value: Value) -> HyperThread
+	{
+		let (&(queue, fixed_sized_message_body_compressed_type_identifier), actual_hyper_thread) = self.queues_mapped.get_or(hyper_thread, self.default_hyper_thread);
+		
+		unsafe { (& * queue).enqueue_or_dead_letter(fixed_sized_message_body_compressed_type_identifier, priority, MessageHeaderExtensions::default(), 0, |uninitialized_memory| TraitObjectMessageBody::<Trait, Value>::construct(uninitialized_memory, value)) };
+		actual_hyper_thread
+	}
+	
+	/// A publisher publishes to a specific hyper thread, moving `value` into the queue, without waiting for space in the queue.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	///
+	/// Returns the actual hyper thread published to, or, if its queue is full, a `PublishError::QueueFull` which hands back `value`.
+	#[inline(always)]
+	pub fn try_publish<Value: 'static + Unsize<Trait>>(&self, hyper_thread: HyperThread, value: Value) -> Result<HyperThread, PublishError<Value>>
+	{
+		self.try_publish_with_priority(hyper_thread, Priority::Highest, value)
+	}
+	
+	/// A publisher publishes to the priority lane for `priority` of a specific hyper thread, moving `value` into the queue, without waiting for space in the queue.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	/// If the queue has fewer priority lanes than `priority` needs, publishes to its lowest priority lane.
+	///
+	/// Returns the actual hyper thread published to, or, if its priority lane is full, a `PublishError::QueueFull` which hands back `value`.
+	#[inline(always)]
+	pub fn try_publish_with_priority<Value: 'static + Unsize<Trait>>(&self, hyper_thread: HyperThread, priority: Priority, value: Value) -> Result<HyperThread, PublishError<Value>>
+	{
+		let (&(queue, fixed_sized_message_body_compressed_type_identifier), actual_hyper_thread) = self.queues_mapped.get_or(hyper_thread, self.default_hyper_thread);
+		
+		unsafe { (& * queue).try_enqueue_or_dead_letter(actual_hyper_thread, fixed_sized_message_body_compressed_type_identifier, priority, MessageHeaderExtensions::default(), 0, value, |uninitialized_memory, value| TraitObjectMessageBody::<Trait, Value>::construct(uninitialized_memory, value)) }
+	}
+}
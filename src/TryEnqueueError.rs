@@ -0,0 +1,42 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// Why a message could not be enqueued without waiting for space in a queue.
+///
+/// In either case, `fixed_size_message_body_constructor` was not called, and the unconsumed `construct_message_arguments` are handed back.
+#[derive(Debug)]
+enum TryEnqueueError<ConstructMessageArguments>
+{
+	/// The priority lane did not have space for a message of `bytes_needed`.
+	QueueFull
+	{
+		bytes_needed: usize,
+		
+		construct_message_arguments: ConstructMessageArguments,
+	},
+	
+	/// A message of `bytes_needed` could never be enqueued, as it is too large or too aligned to be represented or larger than the priority lane.
+	TooLarge
+	{
+		bytes_needed: usize,
+		
+		construct_message_arguments: ConstructMessageArguments,
+	},
+}
+
+impl<ConstructMessageArguments> TryEnqueueError<ConstructMessageArguments>
+{
+	#[inline(always)]
+	fn into_publish_error(self, hyper_thread: HyperThread) -> PublishError<ConstructMessageArguments>
+	{
+		use self::TryEnqueueError::*;
+		
+		match self
+		{
+			QueueFull { bytes_needed, construct_message_arguments } => PublishError::QueueFull { hyper_thread, bytes_needed, construct_message_arguments },
+			
+			TooLarge { bytes_needed, construct_message_arguments } => PublishError::TooLarge { hyper_thread, bytes_needed, construct_message_arguments },
+		}
+	}
+}
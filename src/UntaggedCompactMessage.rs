@@ -0,0 +1,34 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A message in a compact lane, untagged on the subscriber's stack so that it can be handled, dropped in place or moved to a dead letter sink as if it were an ordinary message.
+///
+/// Has the same representation as a `CompactMessage<T>` for any `T`, as `Option<Box<T>>` has the same representation as `NonNull<()>`; it never drops `boxed` itself.
+#[repr(C)]
+#[derive(Debug)]
+struct UntaggedCompactMessage
+{
+	boxed: NonNull<()>,
+	opcode: u32,
+}
+
+impl UntaggedCompactMessage
+{
+	#[inline(always)]
+	fn untag(tagged_non_null_pointer: TaggedNonNullPointer) -> Self
+	{
+		Self
+		{
+			boxed: tagged_non_null_pointer.non_null_pointer().expect("Compact messages are never special"),
+			opcode: tagged_non_null_pointer.tag_bits(),
+		}
+	}
+	
+	/// A pointer to a message body of `CompactMessage<T>`.
+	#[inline(always)]
+	fn as_variably_sized_message_body(&mut self) -> NonNull<VariablySizedMessageBody>
+	{
+		NonNull::from(self).cast()
+	}
+}
@@ -0,0 +1,105 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A publisher for one type of value, `FixedSizeMessageBody`, which is moved into a queue.
+///
+/// Unlike `Publisher`, this is entirely safe: there is no need to implement `Message` and its in-place `construct_message()`.
+///
+/// See `Publisher` for why this holds a `*const Queue`, and for what happens when publishing to a queue whose `MessageHandlers` do not have `FixedSizeMessageBody` registered.
+#[derive(Debug)]
+pub struct ValuePublisher<FixedSizeMessageBody: 'static + Sized, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>
+{
+	_queues_drop_reference: Queues<MessageHandlerArguments, DequeuedMessageProcessingError>,
+	queues_mapped: PerBitSetAwareData<HyperThread, (*const Queue<MessageHandlerArguments, DequeuedMessageProcessingError>, Option<CompressedTypeIdentifier>)>,
+	default_hyper_thread: HyperThread,
+	marker: PhantomData<FixedSizeMessageBody>,
+}
+
+impl<FixedSizeMessageBody: 'static + Sized, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> ValuePublisher<FixedSizeMessageBody, MessageHandlerArguments, DequeuedMessageProcessingError>
+{
+	#[inline(always)]
+	fn new(queues: &Queues<MessageHandlerArguments, DequeuedMessageProcessingError>, default_hyper_thread: HyperThread) -> Self
+	{
+		Self
+		{
+			_queues_drop_reference: queues.clone(),
+			queues_mapped: queues.0.map_ref(|_hyper_thread, queue| (queue as *const _, queue.fixed_sized_message_body_compressed_type_identifier::<FixedSizeMessageBody>())),
+			default_hyper_thread,
+			marker: PhantomData,
+		}
+	}
+	
+	/// The queue `hyper_thread` publishes to.
+	#[inline(always)]
+	fn queue(&self, hyper_thread: HyperThread) -> &Queue<MessageHandlerArguments, DequeuedMessageProcessingError>
+	{
+		let (&(queue, _fixed_sized_message_body_compressed_type_identifier), _actual_hyper_thread) = self.queues_mapped.get_or(hyper_thread, self.default_hyper_thread);
+		unsafe { & * queue }
+	}
+	
+	/// The actual hyper thread `hyper_thread` publishes to, as `Ok` if `FixedSizeMessageBody` is registered with its queue and as `Err` if not.
+	#[inline(always)]
+	fn actual_hyper_thread_if_registered(&self, hyper_thread: HyperThread) -> Result<HyperThread, HyperThread>
+	{
+		let (&(_queue, fixed_sized_message_body_compressed_type_identifier), actual_hyper_thread) = self.queues_mapped.get_or(hyper_thread, self.default_hyper_thread);
+		
+		match fixed_sized_message_body_compressed_type_identifier
+		{
+			None => Err(actual_hyper_thread),
+			
+			Some(_) => Ok(actual_hyper_thread),
+		}
+	}
+	
+	/// A publisher publishes to a specific hyper thread, moving `value` into the queue.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish(&self, hyper_thread: HyperThread, value: FixedSizeMessageBody) -> HyperThread
+	{
+		self.publish_with_priority(hyper_thread, Priority::Highest, value)
+	}
+	
+	/// A publisher publishes to the priority lane for `priority` of a specific hyper thread, moving `value` into the queue.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	/// If the queue has fewer priority lanes than `priority` needs, publishes to its lowest priority lane.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish_with_priority(&self, hyper_thread: HyperThread, priority: Priority, value: FixedSizeMessageBody) -> HyperThread
+	{
+		let (&(queue, fixed_sized_message_body_compressed_type_identifier), actual_hyper_thread) = self.queues_mapped.get_or(hyper_thread, self.default_hyper_thread);
+		
+		unsafe { (& * queue).enqueue_or_dead_letter(fixed_sized_message_body_compressed_type_identifier, priority, MessageHeaderExtensions::default(), 0, |uninitialized_memory| write(uninitialized_memory.as_ptr(), value)) };
+		actual_hyper_thread
+	}
+	
+	/// A publisher publishes to a specific hyper thread, moving `value` into the queue, without waiting for space in the queue.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	///
+	/// Returns the actual hyper thread published to, or, if its queue is full, a `PublishError::QueueFull` which hands back `value`.
+	#[inline(always)]
+	pub fn try_publish(&self, hyper_thread: HyperThread, value: FixedSizeMessageBody) -> Result<HyperThread, PublishError<FixedSizeMessageBody>>
+	{
+		self.try_publish_with_priority(hyper_thread, Priority::Highest, value)
+	}
+	
+	/// A publisher publishes to the priority lane for `priority` of a specific hyper thread, moving `value` into the queue, without waiting for space in the queue.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	/// If the queue has fewer priority lanes than `priority` needs, publishes to its lowest priority lane.
+	///
+	/// Returns the actual hyper thread published to, or, if its priority lane is full, a `PublishError::QueueFull` which hands back `value`.
+	#[inline(always)]
+	pub fn try_publish_with_priority(&self, hyper_thread: HyperThread, priority: Priority, value: FixedSizeMessageBody) -> Result<HyperThread, PublishError<FixedSizeMessageBody>>
+	{
+		let (&(queue, fixed_sized_message_body_compressed_type_identifier), actual_hyper_thread) = self.queues_mapped.get_or(hyper_thread, self.default_hyper_thread);
+		
+		unsafe { (& * queue).try_enqueue_or_dead_letter(actual_hyper_thread, fixed_sized_message_body_compressed_type_identifier, priority, MessageHeaderExtensions::default(), 0, value, |uninitialized_memory, value| write(uninitialized_memory.as_ptr(), value)) }
+	}
+}
@@ -0,0 +1,104 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A publisher for one type of variably sized message, consisting of a header, `Header`, and a payload of bytes.
+///
+/// The header and payload are written directly into the queue's magic ring buffer; there is no heap allocation.
+///
+/// See `Publisher` for why this holds a `*const Queue`, and for what happens when publishing to a queue whose `MessageHandlers` do not have `Header` registered.
+#[derive(Debug)]
+pub struct VariablySizedPublisher<Header: 'static + VariablySizedMessage<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>
+{
+	_queues_drop_reference: Queues<MessageHandlerArguments, DequeuedMessageProcessingError>,
+	queues_mapped: PerBitSetAwareData<HyperThread, (*const Queue<MessageHandlerArguments, DequeuedMessageProcessingError>, Option<CompressedTypeIdentifier>)>,
+	default_hyper_thread: HyperThread,
+	marker: PhantomData<Header>,
+}
+
+impl<Header: 'static + VariablySizedMessage<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> VariablySizedPublisher<Header, MessageHandlerArguments, DequeuedMessageProcessingError>
+{
+	#[inline(always)]
+	fn new(queues: &Queues<MessageHandlerArguments, DequeuedMessageProcessingError>, default_hyper_thread: HyperThread) -> Self
+	{
+		Self
+		{
+			_queues_drop_reference: queues.clone(),
+			queues_mapped: queues.0.map_ref(|_hyper_thread, queue| (queue as *const _, queue.fixed_sized_message_body_compressed_type_identifier::<HeaderAndPayload<Header>>())),
+			default_hyper_thread,
+			marker: PhantomData,
+		}
+	}
+	
+	/// Publishes `header` followed by a copy of `payload` to a specific hyper thread.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish(&self, hyper_thread: HyperThread, header: Header, payload: &[u8]) -> HyperThread
+	{
+		self.publish_with_priority(hyper_thread, Priority::Highest, header, payload)
+	}
+	
+	/// Publishes `header` followed by a copy of `payload` to the priority lane for `priority` of a specific hyper thread.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	/// If the queue has fewer priority lanes than `priority` needs, publishes to its lowest priority lane.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish_with_priority(&self, hyper_thread: HyperThread, priority: Priority, header: Header, payload: &[u8]) -> HyperThread
+	{
+		self.publish_with_priority_and_payload_writer(hyper_thread, priority, header, payload.len(), |uninitialized_payload| uninitialized_payload.copy_from_slice(payload))
+	}
+	
+	/// Publishes `header` followed by `payload_length` bytes of payload written in place by `payload_writer` to a specific hyper thread.
+	///
+	/// `payload_writer` must initialize all of the bytes it is given.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish_with(&self, hyper_thread: HyperThread, header: Header, payload_length: usize, payload_writer: impl FnOnce(&mut [u8])) -> HyperThread
+	{
+		self.publish_with_priority_and_payload_writer(hyper_thread, Priority::Highest, header, payload_length, payload_writer)
+	}
+	
+	/// As for `publish_with()`, but publishes to the priority lane for `priority`.
+	///
+	/// If the queue has fewer priority lanes than `priority` needs, publishes to its lowest priority lane.
+	#[inline(always)]
+	pub fn publish_with_priority_and_payload_writer(&self, hyper_thread: HyperThread, priority: Priority, header: Header, payload_length: usize, payload_writer: impl FnOnce(&mut [u8])) -> HyperThread
+	{
+		let (&(queue, fixed_sized_message_body_compressed_type_identifier), actual_hyper_thread) = self.queues_mapped.get_or(hyper_thread, self.default_hyper_thread);
+		
+		unsafe { (& * queue).enqueue_or_dead_letter(fixed_sized_message_body_compressed_type_identifier, priority, MessageHeaderExtensions::default(), payload_length, |uninitialized_memory| HeaderAndPayload::construct(uninitialized_memory, header, payload_length, payload_writer)) };
+		actual_hyper_thread
+	}
+	
+	/// Publishes `header` followed by a copy of `payload` to a specific hyper thread without waiting for space in the queue.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	///
+	/// Returns the actual hyper thread published to, or, if its queue is full, a `PublishError::QueueFull` which hands back the unconsumed `header`.
+	#[inline(always)]
+	pub fn try_publish(&self, hyper_thread: HyperThread, header: Header, payload: &[u8]) -> Result<HyperThread, PublishError<Header>>
+	{
+		self.try_publish_with_priority(hyper_thread, Priority::Highest, header, payload)
+	}
+	
+	/// Publishes `header` followed by a copy of `payload` to the priority lane for `priority` of a specific hyper thread without waiting for space in the queue.
+	///
+	/// If there is no queue for the hyper thread, publishes to itself.
+	/// If the queue has fewer priority lanes than `priority` needs, publishes to its lowest priority lane.
+	///
+	/// Returns the actual hyper thread published to, or, if its priority lane is full, a `PublishError::QueueFull` which hands back the unconsumed `header`.
+	#[inline(always)]
+	pub fn try_publish_with_priority(&self, hyper_thread: HyperThread, priority: Priority, header: Header, payload: &[u8]) -> Result<HyperThread, PublishError<Header>>
+	{
+		let (&(queue, fixed_sized_message_body_compressed_type_identifier), actual_hyper_thread) = self.queues_mapped.get_or(hyper_thread, self.default_hyper_thread);
+		
+		let payload_length = payload.len();
+		unsafe { (& * queue).try_enqueue_or_dead_letter(actual_hyper_thread, fixed_sized_message_body_compressed_type_identifier, priority, MessageHeaderExtensions::default(), payload_length, header, |uninitialized_memory, header| HeaderAndPayload::construct(uninitialized_memory, header, payload_length, |uninitialized_payload| uninitialized_payload.copy_from_slice(payload))) }
+	}
+}
@@ -0,0 +1,24 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A message consisting of a fixed size header, `Self`, followed by a variably sized payload which is a string.
+///
+/// As for `VariablySizedMessage`, both the header and the payload are written directly into the queue's magic ring buffer; there is no heap allocation when publishing or handling.
+///
+/// Register using `MessageHandlers::register_variably_sized_str_message_handler()` and publish using a `StrPublisher`, which only accepts a `&str` payload; the payload is therefore always valid UTF-8 and is not checked again when handled.
+pub trait VariablySizedStrMessage: Sized
+{
+	/// Message handler arguments.
+	type MessageHandlerArguments;
+	
+	/// Error that can happen when processing a dequeued message.
+	type DequeuedMessageProcessingError: error::Error;
+	
+	/// Handle a message (used by a receiving thread).
+	///
+	/// `payload` is the string published with this header; it is only valid for the duration of this call.
+	///
+	/// Messages are automatically dropped after this has been called; the payload is just bytes and so is never dropped.
+	fn handle_message(&mut self, payload: &mut str, message_handler_arguments: &Self::MessageHandlerArguments) -> Result<(), Self::DequeuedMessageProcessingError>;
+}
@@ -0,0 +1,82 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A weighted round-robin publisher.
+///
+/// Each of a set (`hyper_threads_to_publish_to_and_weights`) of `HyperThread`s receives a share of messages in proportion to its weight.
+/// Uses smooth weighted round-robin, so that messages to a heavily weighted hyper thread are interleaved with those to others rather than sent in bursts.
+///
+/// Not thread safe.
+#[derive(Debug)]
+pub struct WeightedRoundRobinPublisher<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error>
+{
+	publisher: Publisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>,
+	hyper_threads_to_publish_to_and_weights: Box<[(HyperThread, NonZeroU32)]>,
+	current_weights: Box<[Cell<i64>]>,
+	total_weight: i64,
+}
+
+impl<M: 'static + Message<MessageHandlerArguments=MessageHandlerArguments, DequeuedMessageProcessingError=DequeuedMessageProcessingError>, MessageHandlerArguments, DequeuedMessageProcessingError: error::Error> WeightedRoundRobinPublisher<M, MessageHandlerArguments, DequeuedMessageProcessingError>
+{
+	#[inline(always)]
+	fn new(queues: &Queues<MessageHandlerArguments, DequeuedMessageProcessingError>, hyper_threads_to_publish_to_and_weights: Box<[(HyperThread, NonZeroU32)]>) -> Self
+	{
+		queues.assert_hyper_threads_to_publish_to_have_queues(&hyper_threads_to_publish_to_and_weights.iter().map(|&(hyper_thread, _weight)| hyper_thread).collect::<Vec<_>>());
+		let default_hyper_thread = hyper_threads_to_publish_to_and_weights.get_unchecked_safe(0).0;
+		
+		Self
+		{
+			publisher: Publisher::new(queues, default_hyper_thread),
+			current_weights: hyper_threads_to_publish_to_and_weights.iter().map(|_| Cell::new(0)).collect(),
+			total_weight: hyper_threads_to_publish_to_and_weights.iter().map(|&(_, weight)| weight.get() as i64).sum(),
+			hyper_threads_to_publish_to_and_weights,
+		}
+	}
+	
+	/// Publishes to the next hyper thread by weight.
+	///
+	/// Returns the actual hyper thread published to.
+	#[inline(always)]
+	pub fn publish(&self, construct_message_arguments: M::ConstructMessageArguments) -> HyperThread
+	{
+		self.publisher.publish(self.next_hyper_thread(), construct_message_arguments)
+	}
+	
+	/// Publishes to the next hyper thread by weight without waiting for space in the queue.
+	///
+	/// See `RoundRobinPublisher::try_publish()` for what happens when the queue is full.
+	///
+	/// Returns the actual hyper thread published to, or, if its queue is full, a `PublishError::QueueFull` which hands back the unconsumed `construct_message_arguments`.
+	#[inline(always)]
+	pub fn try_publish(&self, construct_message_arguments: M::ConstructMessageArguments) -> Result<HyperThread, PublishError<M::ConstructMessageArguments>>
+	{
+		self.publisher.try_publish(self.next_hyper_thread(), construct_message_arguments)
+	}
+	
+	/// Every current weight is increased by its weight, then the hyper thread with the largest current weight is chosen and its current weight is reduced by the total weight.
+	#[inline(always)]
+	fn next_hyper_thread(&self) -> HyperThread
+	{
+		let mut chosen_index = 0;
+		let mut largest_current_weight = i64::MIN;
+		
+		for (index, &(_, weight)) in self.hyper_threads_to_publish_to_and_weights.iter().enumerate()
+		{
+			let current_weight = self.current_weights.get_unchecked_safe(index);
+			let increased_current_weight = current_weight.get() + (weight.get() as i64);
+			current_weight.set(increased_current_weight);
+			
+			if increased_current_weight > largest_current_weight
+			{
+				chosen_index = index;
+				largest_current_weight = increased_current_weight;
+			}
+		}
+		
+		let chosen_current_weight = self.current_weights.get_unchecked_safe(chosen_index);
+		chosen_current_weight.set(chosen_current_weight.get() - self.total_weight);
+		
+		self.hyper_threads_to_publish_to_and_weights.get_unchecked_safe(chosen_index).0
+	}
+}
@@ -12,6 +12,7 @@
 #![feature(extern_types)]
 #![feature(integer_atomics)]
 #![feature(raw)]
+#![feature(unsize)]
 
 
 //! #message-dispatch
@@ -33,29 +34,101 @@ assert_cfg!(target_pointer_width = "64");
 use self::message::*;
 use self::message_handling::*;
 use self::virtual_method_tables::*;
-use arrayvec::ArrayVec;
+#[cfg(not(feature = "wide-compressed-type-identifier"))] use arrayvec::ArrayVec;
+use libc::__errno_location;
+use libc::c_long;
+use libc::c_void;
+use libc::clock_gettime;
+use libc::close;
+use libc::CLOCK_MONOTONIC;
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))] use libc::CLOCK_MONOTONIC_RAW;
+use libc::EFD_CLOEXEC;
+use libc::EFD_NONBLOCK;
+use libc::eventfd;
+use libc::pthread_self;
+use libc::read;
+use libc::write as libc_write;
+use libc::EAGAIN;
+use libc::EINTR;
+use libc::ETIMEDOUT;
+use libc::FUTEX_WAIT_PRIVATE;
+use libc::FUTEX_WAKE_PRIVATE;
+use libc::SYS_futex;
+use libc::syscall;
+use libc::time_t;
+use libc::timespec;
 use linux_support::cpu::HyperThread;
 use linux_support::memory::huge_pages::DefaultHugePageSizes;
 use magic_ring_buffer::MagicRingBuffer;
 use magic_ring_buffer::MirroredMemoryMapCreationError;
+use std::alloc::Layout;
+use std::alloc::alloc;
+use std::alloc::dealloc;
+use std::alloc::handle_alloc_error;
 use std::any::Any;
 use std::any::TypeId;
+use std::any::type_name;
+#[cfg(target_arch = "x86_64")] use std::arch::x86_64::_rdtsc;
 use std::cell::Cell;
+use std::cell::RefCell;
+use std::cmp::max;
+use std::cmp::min;
+use std::collections::VecDeque;
+use std::collections::hash_map::DefaultHasher;
 use std::error;
 use std::fmt;
 use std::fmt::Debug;
 use std::fmt::Formatter;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::io;
+use std::hint::spin_loop;
 use std::marker::PhantomData;
+use std::marker::Unsize;
 use std::mem::align_of;
 use std::mem::forget;
 use std::mem::size_of;
+use std::mem::swap;
+use std::mem::take;
 use std::mem::transmute;
+use std::mem::transmute_copy;
+use std::num::NonZeroU32;
 use std::num::NonZeroU64;
+use std::num::NonZeroU8;
+use std::num::NonZeroUsize;
+#[cfg(test)] use std::ops::Range;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::io::RawFd;
+use std::panic::AssertUnwindSafe;
+use std::panic::catch_unwind;
 use std::ptr::NonNull;
+use std::ptr::copy_nonoverlapping;
+use std::ptr::null;
 use std::ptr::null_mut;
+use std::ptr::read;
+use std::ptr::read_unaligned;
 use std::ptr::write;
+use std::ptr::write_unaligned;
+#[cfg(test)] use std::rc::Rc;
+use std::slice::from_raw_parts_mut;
+use std::str::from_utf8_unchecked_mut;
 use std::raw::TraitObject;
 use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::AcqRel;
+use std::sync::atomic::Ordering::Acquire;
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::atomic::Ordering::Release;
+use std::sync::atomic::Ordering::SeqCst;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Waker;
+use std::thread::yield_now;
+use std::time::Duration;
 use swiss_army_knife::bit_set::BitSet;
 use swiss_army_knife::bit_set::PerBitSetAwareData;
 use swiss_army_knife::get_unchecked::GetUnchecked;
@@ -63,6 +136,9 @@ use swiss_army_knife::hash_map_and_hash_set::FastSecureHashMap as HashMap;
 use swiss_army_knife::non_zero::new_non_null;
 use swiss_army_knife::non_zero::new_non_zero_u64;
 use swiss_army_knife::unsafe_initialization::unsafe_uninitialized;
+#[cfg(test)] use linux_support::paths::ProcPath;
+#[cfg(test)] use linux_support::paths::SysPath;
+#[cfg(test)] use terminate::SimpleTerminate;
 use terminate::Terminate;
 
 
@@ -75,14 +151,66 @@ mod message_handling;
 mod virtual_method_tables;
 
 
+#[cfg(test)]
+mod tests;
+
+
+include!("AsyncSubscriber.rs");
+include!("BroadcastPublisher.rs");
+include!("CompactLane.rs");
+include!("CompactMessage.rs");
+include!("CompactPublisher.rs");
 include!("CompressedTypeIdentifier.rs");
+include!("ConflatedMessage.rs");
+include!("ConflatingPublisher.rs");
+include!("DeadLetter.rs");
+include!("DeadLetterReason.rs");
+include!("DeadLetterSink.rs");
 include!("Dequeue.rs");
+include!("DequeueBudget.rs");
+include!("DequeueFailed.rs");
+include!("DequeueOutcome.rs");
+include!("DequeuedMessageProcessingErrorPolicy.rs");
+include!("DequeueStoppedBecause.rs");
 include!("Enqueue.rs");
+include!("HandlerPanicked.rs");
+include!("LeastLoadedPublisher.rs");
 include!("Message.rs");
+include!("message_set.rs");
 include!("MessageHandlers.rs");
+include!("MessageSet.rs");
+include!("MessageSetMember.rs");
+include!("MonotonicTime.rs");
+include!("OverflowCounters.rs");
+include!("OverflowPolicy.rs");
+include!("PowerOfTwoChoicesPublisher.rs");
+include!("Priority.rs");
+include!("PriorityLane.rs");
+include!("PriorityLaneStarvationGuard.rs");
+include!("PublishError.rs");
 include!("Publisher.rs");
 include!("Queue.rs");
+include!("QueueCreationError.rs");
+include!("QueueEventFileDescriptor.rs");
+include!("QueueOccupancy.rs");
+include!("QueueSettings.rs");
 include!("Queues.rs");
+include!("read_time_stamp_counter.rs");
 include!("round_up_to_alignment.rs");
 include!("RoundRobinPublisher.rs");
+include!("ShardedPublisher.rs");
+include!("SharedRoundRobinPublisher.rs");
+include!("StrPublisher.rs");
 include!("Subscriber.rs");
+include!("SubscriberWaker.rs");
+include!("SubscriberWakeUp.rs");
+include!("TimerWheel.rs");
+include!("TraitObjectMessage.rs");
+include!("TraitObjectPublisher.rs");
+include!("TryEnqueueError.rs");
+include!("UntaggedCompactMessage.rs");
+include!("ValuePublisher.rs");
+include!("VariablySizedMessage.rs");
+include!("VariablySizedStrMessage.rs");
+include!("VariablySizedPublisher.rs");
+include!("WeightedRoundRobinPublisher.rs");
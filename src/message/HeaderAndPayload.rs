@@ -0,0 +1,64 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// The fixed size message body used for a `VariablySizedMessage`.
+///
+/// It is immediately followed by `payload_length` bytes of payload; these are accounted for as trailing bytes when the message is enqueued.
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct HeaderAndPayload<Header: VariablySizedMessage>
+{
+	payload_length: usize,
+	header: Header,
+}
+
+impl<Header: VariablySizedMessage> HeaderAndPayload<Header>
+{
+	/// Constructs in place.
+	///
+	/// `uninitialized_memory` must be followed by at least `payload_length` bytes; `payload_writer` must initialize all of them.
+	#[inline(always)]
+	pub(crate) unsafe fn construct(uninitialized_memory: NonNull<Self>, header: Header, payload_length: usize, payload_writer: impl FnOnce(&mut [u8]))
+	{
+		write(uninitialized_memory.as_ptr(), Self { payload_length, header });
+		payload_writer((& mut * uninitialized_memory.as_ptr()).payload_mut())
+	}
+	
+	/// Registered as the message handler.
+	#[inline(always)]
+	pub(crate) fn handle_message(&mut self, message_handler_arguments: &Header::MessageHandlerArguments) -> Result<(), Header::DequeuedMessageProcessingError>
+	{
+		let payload = unsafe { from_raw_parts_mut(self.payload_pointer(), self.payload_length) };
+		self.header.handle_message(payload, message_handler_arguments)
+	}
+	
+	/// Registered to find the layout including the payload, eg when moving the message to a `DeadLetter`.
+	#[inline(always)]
+	pub(crate) fn layout(variably_sized_message_body: NonNull<VariablySizedMessageBody>) -> Layout
+	{
+		let this = unsafe { & * (variably_sized_message_body.as_ptr() as *const Self) };
+		Layout::from_size_align(size_of::<Self>() + this.payload_length, align_of::<Self>()).unwrap()
+	}
+	
+	/// Moves the header and the payload out.
+	#[inline(always)]
+	pub(crate) unsafe fn into_header_and_payload(this: NonNull<Self>) -> (Header, Box<[u8]>)
+	{
+		let this = &mut * this.as_ptr();
+		let payload = this.payload_mut().to_vec().into_boxed_slice();
+		(read(&this.header), payload)
+	}
+	
+	#[inline(always)]
+	fn payload_mut(&mut self) -> &mut [u8]
+	{
+		unsafe { from_raw_parts_mut(self.payload_pointer(), self.payload_length) }
+	}
+	
+	#[inline(always)]
+	fn payload_pointer(&mut self) -> *mut u8
+	{
+		(self as *mut Self as usize + size_of::<Self>()) as *mut u8
+	}
+}
@@ -2,23 +2,38 @@
 // Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
 
 
+/// The total message size is a `u32`, so a message, including its `MessageHeader`, can be up to 4 GiB less one byte.
+///
+/// The header is 8 bytes (whether the `CompressedTypeIdentifier` is 1 or 2 bytes) and so messages are aligned to 4 bytes.
+///
+/// The alignment of the message body is stored as its logarithm base 2 so that any power-of-two alignment can be represented; the message body starts at the first such aligned address after the header and its `MessageHeaderExtensions`, if any.
 #[derive(Debug)]
 #[repr(C)]
 struct MessageHeader
 {
 	compressed_type_identifier: CompressedTypeIdentifier,
-	number_of_bytes_padding_to_align_message_body: u8,
-	total_message_size_including_message_header_padding_to_align_before_message_body_and_padding_to_align_after: u16,
+	message_body_alignment_logarithm_base_2: u8,
+	message_header_extension_flags: u8,
+	total_message_size_including_message_header_padding_to_align_before_message_body_and_padding_to_align_after: u32,
 }
 
 impl MessageHeader
 {
+	/// Inclusive maximum.
+	const MaximumTotalMessageSize: usize = u32::MAX as usize;
+	
 	#[inline(always)]
 	fn variably_sized_message_body(&mut self) -> NonNull<VariablySizedMessageBody>
 	{
 		new_non_null(self.message_body_pointer() as *mut VariablySizedMessageBody)
 	}
 
+	#[inline(always)]
+	fn message_header_extensions(&self) -> MessageHeaderExtensions
+	{
+		unsafe { MessageHeaderExtensions::read(self.message_header_extension_flags, self.message_header_extensions_pointer()) }
+	}
+	
 	#[inline(always)]
 	fn total_message_size_including_message_header_padding_to_align_before_message_body_and_padding_to_align_after(&self) -> usize
 	{
@@ -28,9 +43,17 @@ impl MessageHeader
 	#[inline(always)]
 	fn message_body_pointer(&self) -> usize
 	{
-		self.base_pointer() + self.number_of_bytes_padding_to_align_message_body()
+		let message_body_alignment = self.message_body_alignment();
+		let first_possible_message_body_pointer = self.message_header_extensions_pointer() + MessageHeaderExtensions::size_for_flags(self.message_header_extension_flags);
+		(first_possible_message_body_pointer + message_body_alignment - 1) & !(message_body_alignment - 1)
 	}
 
+	#[inline(always)]
+	fn message_header_extensions_pointer(&self) -> usize
+	{
+		self.base_pointer() + size_of::<Self>()
+	}
+	
 	#[inline(always)]
 	fn base_pointer(&self) -> usize
 	{
@@ -38,8 +61,8 @@ impl MessageHeader
 	}
 
 	#[inline(always)]
-	fn number_of_bytes_padding_to_align_message_body(&self) -> usize
+	fn message_body_alignment(&self) -> usize
 	{
-		self.number_of_bytes_padding_to_align_message_body as usize
+		1 << (self.message_body_alignment_logarithm_base_2 as usize)
 	}
 }
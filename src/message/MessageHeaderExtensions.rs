@@ -0,0 +1,140 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// Optional fields of a message, stored immediately after its `MessageHeader` and before any padding to align the message body.
+///
+/// Each present field is an unaligned `u64`; `MessageHeader.message_header_extension_flags` records which are present, so a message without any costs nothing extra.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct MessageHeaderExtensions
+{
+	due_time: Option<MonotonicTime>,
+	expiry_time: Option<MonotonicTime>,
+}
+
+impl MessageHeaderExtensions
+{
+	const DueTimeFlag: u8 = 0x01;
+	
+	const ExpiryTimeFlag: u8 = 0x02;
+	
+	const FieldSize: usize = size_of::<u64>();
+	
+	/// The size of all possible fields.
+	pub(super) const LargestSize: usize = 2 * Self::FieldSize;
+	
+	/// Extensions for a message which should not be handled before `due_time`.
+	#[inline(always)]
+	pub(crate) fn due_at(due_time: MonotonicTime) -> Self
+	{
+		Self
+		{
+			due_time: Some(due_time),
+			expiry_time: None,
+		}
+	}
+	
+	/// Extensions for a message which should not be handled at or after `expiry_time`.
+	#[inline(always)]
+	pub(crate) fn expires_at(expiry_time: MonotonicTime) -> Self
+	{
+		Self
+		{
+			due_time: None,
+			expiry_time: Some(expiry_time),
+		}
+	}
+	
+	/// Extensions for a message which should not be handled before `due_time` nor at or after `expiry_time`.
+	#[inline(always)]
+	pub(crate) fn due_at_and_expires_at(due_time: MonotonicTime, expiry_time: MonotonicTime) -> Self
+	{
+		Self
+		{
+			due_time: Some(due_time),
+			expiry_time: Some(expiry_time),
+		}
+	}
+	
+	/// The message should not be handled before this time.
+	#[inline(always)]
+	pub(crate) fn due_time(&self) -> Option<MonotonicTime>
+	{
+		self.due_time
+	}
+	
+	/// The message should not be handled at or after this time; instead, it is dropped in place.
+	#[inline(always)]
+	pub(crate) fn expiry_time(&self) -> Option<MonotonicTime>
+	{
+		self.expiry_time
+	}
+	
+	#[inline(always)]
+	pub(super) fn size(&self) -> usize
+	{
+		Self::size_for_flags(self.flags())
+	}
+	
+	#[inline(always)]
+	pub(super) fn flags(&self) -> u8
+	{
+		let mut flags = 0;
+		if self.due_time.is_some()
+		{
+			flags |= Self::DueTimeFlag
+		}
+		if self.expiry_time.is_some()
+		{
+			flags |= Self::ExpiryTimeFlag
+		}
+		flags
+	}
+	
+	#[inline(always)]
+	pub(super) fn size_for_flags(flags: u8) -> usize
+	{
+		(flags.count_ones() as usize) * Self::FieldSize
+	}
+	
+	/// `pointer` is the first byte after the `MessageHeader`.
+	///
+	/// Fields are written in the order due time then expiry time.
+	#[inline(always)]
+	pub(super) unsafe fn write(&self, pointer: usize)
+	{
+		let mut pointer = pointer;
+		for time in [self.due_time, self.expiry_time].iter()
+		{
+			if let &Some(time) = time
+			{
+				write_unaligned(pointer as *mut u64, time.nanoseconds());
+				pointer += Self::FieldSize
+			}
+		}
+	}
+	
+	/// `pointer` is the first byte after the `MessageHeader`.
+	#[inline(always)]
+	pub(super) unsafe fn read(flags: u8, pointer: usize) -> Self
+	{
+		let mut pointer = pointer;
+		let mut read_field = |flag: u8|
+		{
+			if flags & flag == 0
+			{
+				return None
+			}
+			
+			let time = MonotonicTime::from_nanoseconds(read_unaligned(pointer as *const u64));
+			pointer += Self::FieldSize;
+			Some(time)
+		};
+		
+		Self
+		{
+			due_time: read_field(Self::DueTimeFlag),
+			expiry_time: read_field(Self::ExpiryTimeFlag),
+		}
+	}
+}
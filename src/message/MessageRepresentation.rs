@@ -13,17 +13,36 @@ pub(super) struct MessageRepresentation
 
 impl MessageRepresentation
 {
+	/// `total_message_size_including_message_header` must be at least `total_message_size_including_message_header::<FixedSizeMessageBody>(message_header_extensions, trailing_bytes)` for the trailing bytes (if any) that `fixed_size_message_body_constructor` writes after the `FixedSizeMessageBody`.
 	#[inline(always)]
-	pub(super) fn enqueue<FixedSizeMessageBody: Sized>(magic_ring_buffer: &MagicRingBuffer, fixed_size_message_body_compressed_type_identifier: CompressedTypeIdentifier, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>))
+	pub(super) fn enqueue<FixedSizeMessageBody: Sized>(magic_ring_buffer: &MagicRingBuffer, total_message_size_including_message_header: usize, fixed_size_message_body_compressed_type_identifier: CompressedTypeIdentifier, message_header_extensions: MessageHeaderExtensions, fixed_size_message_body_constructor: impl FnOnce(NonNull<FixedSizeMessageBody>))
 	{
-		let largest_possible_total_message_size_including_message_header = Self::largest_possible_total_message_size_including_message_header::<FixedSizeMessageBody>();
-
-		magic_ring_buffer.write_some_data(largest_possible_total_message_size_including_message_header, |buffer_sized_as_for_maximum_possible|
+		magic_ring_buffer.write_some_data(total_message_size_including_message_header, |buffer_sized_as_for_maximum_possible|
 		{
-			Self::enqueue_once_buffer_allocated::<FixedSizeMessageBody, _>(buffer_sized_as_for_maximum_possible, fixed_size_message_body_compressed_type_identifier, fixed_size_message_body_constructor)
+			Self::enqueue_once_buffer_allocated::<FixedSizeMessageBody, _>(buffer_sized_as_for_maximum_possible, fixed_size_message_body_compressed_type_identifier, message_header_extensions, fixed_size_message_body_constructor)
 		})
 	}
 	
+	/// The largest message, including its `MessageHeader`, that can be enqueued.
+	pub(super) const MaximumTotalMessageSize: usize = MessageHeader::MaximumTotalMessageSize;
+	
+	/// The largest alignment of a message body.
+	///
+	/// A message body's address is found by rounding up from the address of its `MessageHeader`, so it is only correctly aligned if the `MessageHeader` is read at an address which differs from where it was written by a multiple of the alignment.
+	/// This is the case for a message which wraps around the end of the magic ring buffer (it is written to the mirror but read from the start) as long as the size of the magic ring buffer is a multiple of the alignment.
+	/// The magic ring buffer is always a whole number of its pages; these may be huge pages, but every page size Linux supports is a multiple of 4096 bytes, so 4096 is the largest alignment that holds for every magic ring buffer.
+	/// It is deliberately not derived from the magic ring buffer's actual page size, as then whether a message type could be registered would depend on the huge page sizes available at runtime.
+	///
+	/// Messages spilled to the heap are unaffected, as they are read from the same address they were written to.
+	pub(super) const MaximumMessageBodyAlignment: usize = 4096;
+	
+	/// The alignment a buffer must have to hold a message, as the first thing in a message is a `MessageHeader`.
+	#[inline(always)]
+	pub(super) const fn buffer_alignment() -> usize
+	{
+		align_of::<MessageHeader>()
+	}
+	
 	#[inline(always)]
 	pub(super) fn smallest_possible_total_message_size_including_message_header() -> usize
 	{
@@ -33,82 +52,73 @@ impl MessageRepresentation
 	/// NOTE: In Rust, alignment is *always* a positive power of two (ie never zero), is 1 for packed structs and is never less than the struct's size, either.
 	/// An empty struct by default has an alignment of 1 but it too can have any legal alignment.
 	///
+	/// Allows for all possible `MessageHeaderExtensions`.
+	///
 	/// Should, after monomorphization and compiler optimization, become nothing more than a constant value.
 	#[inline(always)]
 	pub(super) fn largest_possible_total_message_size_including_message_header<FixedSizeMessageBody: Sized>() -> usize
+	{
+		Self::total_message_size_including_message_header_and_message_header_extensions_size::<FixedSizeMessageBody>(MessageHeaderExtensions::LargestSize, 0)
+	}
+	
+	/// As for `largest_possible_total_message_size_including_message_header()`, but with just `message_header_extensions` and with `trailing_bytes` immediately after the `FixedSizeMessageBody`.
+	///
+	/// The total message size is always a multiple of the alignment of a `MessageHeader`, so that the next `MessageHeader` is aligned.
+	#[inline(always)]
+	pub(super) fn total_message_size_including_message_header<FixedSizeMessageBody: Sized>(message_header_extensions: MessageHeaderExtensions, trailing_bytes: usize) -> usize
+	{
+		Self::total_message_size_including_message_header_and_message_header_extensions_size::<FixedSizeMessageBody>(message_header_extensions.size(), trailing_bytes)
+	}
+	
+	#[inline(always)]
+	fn total_message_size_including_message_header_and_message_header_extensions_size<FixedSizeMessageBody: Sized>(message_header_extensions_size: usize, trailing_bytes: usize) -> usize
 	{
 		const MessageHeaderSize: usize = size_of::<MessageHeader>();
 		const MessageHeaderAlignment: usize = align_of::<MessageHeader>();
-		let MessageBodySize = size_of::<FixedSizeMessageBody>();
+		let MessageBodySize = size_of::<FixedSizeMessageBody>() + trailing_bytes;
 		let MessageBodyAlignment = align_of::<FixedSizeMessageBody>();
 
-		if MessageBodyAlignment > MessageHeaderAlignment
+		let maximum_padding_after_message_header_but_before_message_body = if MessageBodyAlignment > MessageHeaderAlignment
 		{
-			let maximum_padding_after_message_header_but_before_message_body = MessageBodyAlignment - MessageHeaderAlignment;
-			let largest_possible_total_message_size_including_message_header = MessageHeaderSize + maximum_padding_after_message_header_but_before_message_body + MessageBodySize;
-
-			largest_possible_total_message_size_including_message_header
+			MessageBodyAlignment - MessageHeaderAlignment
 		}
 		else
 		{
-			let takes_up_no_space = MessageBodySize == 0;
-
-			if takes_up_no_space
-			{
-				MessageHeaderSize
-			}
-			else
-			{
-				MessageHeaderSize * 2
-			}
-		}
+			0
+		};
+		
+		let unpadded_total_message_size = MessageHeaderSize + message_header_extensions_size + maximum_padding_after_message_header_but_before_message_body + MessageBodySize;
+		round_up_to_alignment::<MessageHeader>(unpadded_total_message_size)
 	}
 
 	/// Enqueues a new message into the `buffer_sized_as_for_maximum_possible` if there is space available.
 	///
 	/// Assumes the `buffer_sized_as_for_maximum_possible` is correctly aligned for a `MessageHeader`.
 	#[inline(always)]
-	fn enqueue_once_buffer_allocated<FixedSizeMessageBody: Sized, FixedSizeMessageBodyConstructor: FnOnce(NonNull<FixedSizeMessageBody>)>(buffer_sized_as_for_maximum_possible: &mut [u8], fixed_size_message_body_compressed_type_identifier: CompressedTypeIdentifier, fixed_size_message_body_constructor: FixedSizeMessageBodyConstructor)
+	pub(super) fn enqueue_once_buffer_allocated<FixedSizeMessageBody: Sized, FixedSizeMessageBodyConstructor: FnOnce(NonNull<FixedSizeMessageBody>)>(buffer_sized_as_for_maximum_possible: &mut [u8], fixed_size_message_body_compressed_type_identifier: CompressedTypeIdentifier, message_header_extensions: MessageHeaderExtensions, fixed_size_message_body_constructor: FixedSizeMessageBodyConstructor)
 	{
 		let total_message_size_including_message_header_padding_to_align_before_message_body_and_padding_to_align_after = buffer_sized_as_for_maximum_possible.len();
-		debug_assert_eq!(Self::largest_possible_total_message_size_including_message_header::<FixedSizeMessageBody>(), total_message_size_including_message_header_padding_to_align_before_message_body_and_padding_to_align_after, "buffer_sized_as_for_maximum_possible is not");
-		debug_assert!(total_message_size_including_message_header_padding_to_align_before_message_body_and_padding_to_align_after < u16::MAX as usize, "message is far too large");
+		debug_assert!(Self::total_message_size_including_message_header::<FixedSizeMessageBody>(message_header_extensions, 0) <= total_message_size_including_message_header_padding_to_align_before_message_body_and_padding_to_align_after, "buffer_sized_as_for_maximum_possible is not");
+		assert!(total_message_size_including_message_header_padding_to_align_before_message_body_and_padding_to_align_after <= Self::MaximumTotalMessageSize, "message is far too large");
 
 		let buffer_pointer = buffer_sized_as_for_maximum_possible.as_ptr() as usize;
 		debug_assert_eq!(buffer_pointer % align_of::<MessageHeader>(), 0, "buffer_sized_as_for_maximum_possible is not correctly aligned for a MessageHeader");
 
 		const MessageHeaderSize: usize = size_of::<MessageHeader>();
-		const MessageHeaderAlignment: usize = align_of::<MessageHeader>();
-		let MessageBodySize = size_of::<FixedSizeMessageBody>();
-		let MessageBodyAlignment = align_of::<FixedSizeMessageBody>();
-
-		let (message_body_pointer, number_of_bytes_padding_to_align_message_body) = if MessageBodyAlignment > MessageHeaderAlignment
-		{
-			let first_possible_message_body_pointer = buffer_pointer + MessageHeaderSize;
-			let message_body_pointer = round_up_to_alignment::<FixedSizeMessageBody>(first_possible_message_body_pointer);
-			(message_body_pointer, message_body_pointer - first_possible_message_body_pointer)
-		}
-		else
-		{
-			let takes_up_no_space = MessageBodySize == 0;
-
-			let actual_total_message_size = if takes_up_no_space
-			{
-				MessageHeaderSize
-			}
-			else
-			{
-				MessageHeaderSize * 2
-			};
-			(actual_total_message_size, 0)
-		};
+		let message_header_extensions_pointer = buffer_pointer + MessageHeaderSize;
+		let first_possible_message_body_pointer = message_header_extensions_pointer + message_header_extensions.size();
+		let message_body_pointer = round_up_to_alignment::<FixedSizeMessageBody>(first_possible_message_body_pointer);
+		let message_body_alignment_logarithm_base_2 = align_of::<FixedSizeMessageBody>().trailing_zeros();
+		debug_assert!(align_of::<FixedSizeMessageBody>() <= Self::MaximumMessageBodyAlignment, "FixedSizeMessageBody is too aligned");
 
 		unsafe
 		{
 			let message_header = &mut * (buffer_pointer as *mut MessageHeader);
 			write(&mut message_header.compressed_type_identifier, fixed_size_message_body_compressed_type_identifier);
-			write(&mut message_header.number_of_bytes_padding_to_align_message_body, number_of_bytes_padding_to_align_message_body as u8); // TODO: Could be stored as `SQRT(MessageContentsAlignment)`, thus allowing more alignments, at the cost of more processing when dequeued.
-			write(&mut message_header.total_message_size_including_message_header_padding_to_align_before_message_body_and_padding_to_align_after, total_message_size_including_message_header_padding_to_align_before_message_body_and_padding_to_align_after as u16); // TODO: Could be stored less message header size and leading padding, thus allowing a little more data.
+			write(&mut message_header.message_body_alignment_logarithm_base_2, message_body_alignment_logarithm_base_2 as u8);
+			write(&mut message_header.message_header_extension_flags, message_header_extensions.flags());
+			write(&mut message_header.total_message_size_including_message_header_padding_to_align_before_message_body_and_padding_to_align_after, total_message_size_including_message_header_padding_to_align_before_message_body_and_padding_to_align_after as u32);
+			message_header_extensions.write(message_header_extensions_pointer);
 		}
 
 		fixed_size_message_body_constructor(new_non_null(message_body_pointer as *mut FixedSizeMessageBody))
@@ -116,7 +126,7 @@ impl MessageRepresentation
 
 	/// Returns `(next_message_pointer, R)`.
 	#[inline(always)]
-	pub(super) fn process_next_message_in_buffer<R, MessageProcessor: FnMut(CompressedTypeIdentifier, NonNull<VariablySizedMessageBody>) -> R>(buffer: &mut [u8], mut message_processor: MessageProcessor) -> (usize, R)
+	pub(super) fn process_next_message_in_buffer<R, MessageProcessor: FnMut(CompressedTypeIdentifier, MessageHeaderExtensions, NonNull<VariablySizedMessageBody>) -> R>(buffer: &mut [u8], mut message_processor: MessageProcessor) -> (usize, R)
 	{
 		const MessageHeaderSize: usize = size_of::<MessageHeader>();
 		const MessageHeaderAlignment: usize = align_of::<MessageHeader>();
@@ -134,9 +144,10 @@ impl MessageRepresentation
 		debug_assert_eq!((buffer_pointer + total_message_size_including_message_header_padding_to_align_before_message_body_and_padding_to_align_after) % MessageHeaderAlignment, 0, "Message is not aligned such that the next MessageHeader is aligned");
 
 		let message_body_compressed_type_identifier = message_header.compressed_type_identifier;
+		let message_header_extensions = message_header.message_header_extensions();
 		let variably_sized_message_body = message_header.variably_sized_message_body();
 
-		let outcome = message_processor(message_body_compressed_type_identifier, variably_sized_message_body);
+		let outcome = message_processor(message_body_compressed_type_identifier, message_header_extensions, variably_sized_message_body);
 		(total_message_size_including_message_header_padding_to_align_before_message_body_and_padding_to_align_after, outcome)
 	}
 }
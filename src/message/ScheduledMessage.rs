@@ -0,0 +1,87 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A message which was dequeued before it was due and so was moved to the heap to wait in a timer wheel.
+///
+/// Dropping this frees the heap memory but does ***not*** drop the message body; that is the responsibility of the queue, which knows the message handlers.
+#[derive(Debug)]
+pub(crate) struct ScheduledMessage
+{
+	message_header_extensions: MessageHeaderExtensions,
+	compressed_type_identifier: CompressedTypeIdentifier,
+	body: NonNull<u8>,
+	layout: Layout,
+}
+
+unsafe impl Send for ScheduledMessage
+{
+}
+
+impl Drop for ScheduledMessage
+{
+	#[inline(always)]
+	fn drop(&mut self)
+	{
+		if self.layout.size() != 0
+		{
+			unsafe { dealloc(self.body.as_ptr(), self.layout) }
+		}
+	}
+}
+
+impl ScheduledMessage
+{
+	/// Moves a message out of a queue by copying its bytes; the message in the queue must then be treated as moved from and not dropped.
+	///
+	/// `message_header_extensions` must have a due time; `layout` is that of the message body.
+	#[inline(always)]
+	pub(crate) fn move_from(message_header_extensions: MessageHeaderExtensions, compressed_type_identifier: CompressedTypeIdentifier, layout: Layout, variably_sized_message_body: NonNull<VariablySizedMessageBody>) -> Self
+	{
+		let body = if layout.size() == 0
+		{
+			new_non_null(layout.align() as *mut u8)
+		}
+		else
+		{
+			let body = unsafe { alloc(layout) };
+			if body.is_null()
+			{
+				handle_alloc_error(layout)
+			}
+			unsafe { copy_nonoverlapping(variably_sized_message_body.as_ptr() as *const u8, body, layout.size()) };
+			new_non_null(body)
+		};
+		
+		Self
+		{
+			message_header_extensions,
+			compressed_type_identifier,
+			body,
+			layout,
+		}
+	}
+	
+	/// The message should not be handled before this time.
+	#[inline(always)]
+	pub(crate) fn due_time(&self) -> MonotonicTime
+	{
+		self.message_header_extensions.due_time().expect("Scheduled messages always have a due time")
+	}
+	
+	/// As enqueued.
+	#[inline(always)]
+	pub(crate) fn message_header_extensions(&self) -> MessageHeaderExtensions
+	{
+		self.message_header_extensions
+	}
+	
+	/// Processes the message.
+	///
+	/// Must only be called once.
+	#[inline(always)]
+	pub(crate) fn process<R>(&mut self, message_processor: impl FnOnce(CompressedTypeIdentifier, NonNull<VariablySizedMessageBody>) -> R) -> R
+	{
+		message_processor(self.compressed_type_identifier, self.body.cast())
+	}
+}
@@ -0,0 +1,69 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A message which has been spilled to the heap because there was not space for it in a queue's magic ring buffer.
+///
+/// The heap buffer has exactly the same layout as the message would have had in the magic ring buffer, ie a `MessageRepresentation`.
+///
+/// Dropping this frees the heap buffer but does ***not*** drop the message body; that is the responsibility of the queue, which knows the message handlers.
+#[derive(Debug)]
+pub(crate) struct SpilledMessage
+{
+	buffer: NonNull<u8>,
+	layout: Layout,
+}
+
+unsafe impl Send for SpilledMessage
+{
+}
+
+impl Drop for SpilledMessage
+{
+	#[inline(always)]
+	fn drop(&mut self)
+	{
+		unsafe { dealloc(self.buffer.as_ptr(), self.layout) }
+	}
+}
+
+impl SpilledMessage
+{
+	/// `message_writer` is passed a buffer of exactly `total_message_size_including_message_header` bytes, aligned for a `MessageHeader`.
+	#[inline(always)]
+	pub(crate) fn new(total_message_size_including_message_header: usize, message_writer: impl FnOnce(&mut [u8])) -> Self
+	{
+		let layout = Layout::from_size_align(total_message_size_including_message_header, MessageRepresentation::buffer_alignment()).expect("Message is far too large");
+		let buffer = unsafe { alloc(layout) };
+		if buffer.is_null()
+		{
+			handle_alloc_error(layout)
+		}
+		
+		message_writer(unsafe { from_raw_parts_mut(buffer, total_message_size_including_message_header) });
+		
+		Self
+		{
+			buffer: new_non_null(buffer),
+			layout,
+		}
+	}
+	
+	/// Total message size, as it would have been in a magic ring buffer.
+	#[inline(always)]
+	pub(crate) fn total_message_size(&self) -> usize
+	{
+		self.layout.size()
+	}
+	
+	/// Processes the message.
+	///
+	/// Must only be called once.
+	#[inline(always)]
+	pub(crate) fn process<R>(&mut self, message_processor: impl FnMut(CompressedTypeIdentifier, MessageHeaderExtensions, NonNull<VariablySizedMessageBody>) -> R) -> R
+	{
+		let buffer = unsafe { from_raw_parts_mut(self.buffer.as_ptr(), self.layout.size()) };
+		let (_total_message_size, outcome) = MessageRepresentation::process_next_message_in_buffer(buffer, message_processor);
+		outcome
+	}
+}
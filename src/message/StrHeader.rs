@@ -0,0 +1,24 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// Adapts a `VariablySizedStrMessage` to a `VariablySizedMessage`.
+///
+/// Only ever published by a `StrPublisher`, so the payload is always valid UTF-8.
+#[derive(Debug)]
+#[repr(transparent)]
+pub(crate) struct StrHeader<Header: VariablySizedStrMessage>(pub(crate) Header);
+
+impl<Header: VariablySizedStrMessage> VariablySizedMessage for StrHeader<Header>
+{
+	type MessageHandlerArguments = Header::MessageHandlerArguments;
+	
+	type DequeuedMessageProcessingError = Header::DequeuedMessageProcessingError;
+	
+	#[inline(always)]
+	fn handle_message(&mut self, payload: &mut [u8], message_handler_arguments: &Self::MessageHandlerArguments) -> Result<(), Self::DequeuedMessageProcessingError>
+	{
+		let payload = unsafe { from_utf8_unchecked_mut(payload) };
+		self.0.handle_message(payload, message_handler_arguments)
+	}
+}
@@ -0,0 +1,68 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// The fixed size message body used for a `TraitObjectMessage`.
+///
+/// `TraitObjectMessageBody<Trait, ()>` is registered for all values; the layout of `Value` is then found using `virtual_method_table_pointer` when dequeued.
+#[repr(C)]
+pub(crate) struct TraitObjectMessageBody<Trait: ?Sized + TraitObjectMessage, Value>
+{
+	virtual_method_table_pointer: VirtualMethodTablePointer,
+	value: Value,
+	marker: PhantomData<Trait>,
+}
+
+impl<Trait: ?Sized + TraitObjectMessage, Value: Unsize<Trait>> TraitObjectMessageBody<Trait, Value>
+{
+	/// Constructs in place.
+	#[inline(always)]
+	pub(crate) unsafe fn construct(uninitialized_memory: NonNull<Self>, value: Value)
+	{
+		let virtual_method_table_pointer = VirtualMethodTablePointer::from_unsize::<Value, Trait>(&value);
+		write(uninitialized_memory.as_ptr(), Self { virtual_method_table_pointer, value, marker: PhantomData })
+	}
+}
+
+impl<Trait: ?Sized + TraitObjectMessage> TraitObjectMessageBody<Trait, ()>
+{
+	/// Registered as the message handler.
+	#[inline(always)]
+	pub(crate) fn handle_message(&mut self, message_handler_arguments: &Trait::MessageHandlerArguments) -> Result<(), Trait::DequeuedMessageProcessingError>
+	{
+		let trait_object = self.virtual_method_table_pointer.trait_object::<Trait>(self.value_pointer());
+		unsafe { (& mut * trait_object.as_ptr()).handle_message(message_handler_arguments) }
+	}
+	
+	/// Registered as the drop in place function.
+	#[inline(always)]
+	pub(crate) fn drop_in_place(variably_sized_message_body: NonNull<VariablySizedMessageBody>)
+	{
+		let this = unsafe { & * (variably_sized_message_body.as_ptr() as *const Self) };
+		(this.virtual_method_table_pointer.drop_in_place_function_pointer())(this.value_pointer())
+	}
+	
+	/// Registered to find the layout including the value, eg when moving the message to a `DeadLetter`.
+	#[inline(always)]
+	pub(crate) fn layout(variably_sized_message_body: NonNull<VariablySizedMessageBody>) -> Layout
+	{
+		let this = unsafe { & * (variably_sized_message_body.as_ptr() as *const Self) };
+		let value_alignment = this.virtual_method_table_pointer.alignment();
+		let alignment = max(align_of::<VirtualMethodTablePointer>(), value_alignment);
+		Layout::from_size_align(this.value_offset() + this.virtual_method_table_pointer.size(), alignment).unwrap().pad_to_align()
+	}
+	
+	#[inline(always)]
+	fn value_pointer(&self) -> NonNull<()>
+	{
+		new_non_null((self as *const Self as usize + self.value_offset()) as *mut ())
+	}
+	
+	/// `#[repr(C)]` places `value` at the first offset after `virtual_method_table_pointer` aligned for `Value`.
+	#[inline(always)]
+	fn value_offset(&self) -> usize
+	{
+		let value_alignment = self.virtual_method_table_pointer.alignment();
+		(size_of::<VirtualMethodTablePointer>() + value_alignment - 1) & !(value_alignment - 1)
+	}
+}
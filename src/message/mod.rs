@@ -5,8 +5,14 @@
 use super::*;
 
 
+include!("HeaderAndPayload.rs");
 include!("MessageHeader.rs");
+include!("MessageHeaderExtensions.rs");
 include!("MessageRepresentation.rs");
+include!("ScheduledMessage.rs");
+include!("SpilledMessage.rs");
+include!("StrHeader.rs");
+include!("TraitObjectMessageBody.rs");
 include!("VariablySizedMessageBody.rs");
 include!("VariablySizedPadding.rs");
 include!("VariablySizedPaddingThenAVariablySizedMessageBodyThenVariablySizedPadding.rs");
@@ -2,9 +2,28 @@
 // Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
 
 
+/// A type-erased message handler, which is either a function pointer or a boxed closure.
+///
+/// `closure` is either the function pointer itself or a pointer to the boxed closure; `message_handler` is monomorphized to know which.
 pub(crate) struct MessageHandler<MessageHandlerArguments, MessageHandlerReturns>
 {
-	message_handler: fn(NonNull<VariablySizedMessageBody>, &MessageHandlerArguments) -> MessageHandlerReturns,
+	message_handler: fn(NonNull<()>, NonNull<VariablySizedMessageBody>, &MessageHandlerArguments) -> MessageHandlerReturns,
+	closure: NonNull<()>,
+	drop_closure: fn(NonNull<()>),
+}
+
+/// Only function pointers and closures which are `Send` are ever wrapped.
+unsafe impl<MessageHandlerArguments, MessageHandlerReturns> Send for MessageHandler<MessageHandlerArguments, MessageHandlerReturns>
+{
+}
+
+impl<MessageHandlerArguments, MessageHandlerReturns> Drop for MessageHandler<MessageHandlerArguments, MessageHandlerReturns>
+{
+	#[inline(always)]
+	fn drop(&mut self)
+	{
+		(self.drop_closure)(self.closure)
+	}
 }
 
 impl<MessageHandlerArguments, MessageHandlerReturns> Debug for MessageHandler<MessageHandlerArguments, MessageHandlerReturns>
@@ -12,28 +31,69 @@ impl<MessageHandlerArguments, MessageHandlerReturns> Debug for MessageHandler<Me
 	#[inline(always)]
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result
 	{
-		write!(f, "MessageHandler({:?})", self.message_handler as usize)
+		write!(f, "MessageHandler({:?})", self.closure)
 	}
 }
 
 impl<MessageHandlerArguments, MessageHandlerReturns> MessageHandler<MessageHandlerArguments, MessageHandlerReturns>
 {
-	/// Creates a new instance, wrapping `function`.
-	///
-	/// `function` will be moved from the stack to the heap.
+	/// Creates a new instance, wrapping the function pointer `message_handler`.
 	#[inline(always)]
 	pub(crate) fn new<FixedSizedMessageBody: Sized>(message_handler: fn(&mut FixedSizedMessageBody, &MessageHandlerArguments) -> MessageHandlerReturns) -> Self
 	{
 		Self
 		{
-			message_handler: unsafe { transmute(message_handler) },
+			message_handler: Self::call_function_pointer::<FixedSizedMessageBody>,
+			closure: new_non_null(message_handler as *mut ()),
+			drop_closure: Self::drop_function_pointer,
+		}
+	}
+	
+	/// Creates a new instance, wrapping the closure `message_handler`.
+	///
+	/// `message_handler` will be moved from the stack to the heap.
+	///
+	/// `message_handler` must not borrow anything, as it lives as long as the `MessageHandlers`; it is not required to be `'static` only so that closures wrapping a `'static` closure can be generic over `MessageHandlerArguments`.
+	#[inline(always)]
+	pub(crate) fn new_boxed<FixedSizedMessageBody: Sized, Closure: Send + Fn(&mut FixedSizedMessageBody, &MessageHandlerArguments) -> MessageHandlerReturns>(message_handler: Closure) -> Self
+	{
+		Self
+		{
+			message_handler: Self::call_closure::<FixedSizedMessageBody, Closure>,
+			closure: new_non_null(Box::into_raw(Box::new(message_handler)) as *mut ()),
+			drop_closure: Self::drop_boxed_closure::<Closure>,
 		}
 	}
 
-	/// A very dangerous method that will fail in subtle yet fatal ways if `VariablySizedMessageBody` is not the same type as `FixedSizedMessageBody` in `new()`.
+	/// A very dangerous method that will fail in subtle yet fatal ways if `VariablySizedMessageBody` is not the same type as `FixedSizedMessageBody` in `new()` or `new_boxed()`.
 	#[inline(always)]
 	pub(crate) fn call(&self, variably_sized_message_body: NonNull<VariablySizedMessageBody>, arguments: &MessageHandlerArguments) -> MessageHandlerReturns
 	{
-		(self.message_handler)(variably_sized_message_body, arguments)
+		(self.message_handler)(self.closure, variably_sized_message_body, arguments)
+	}
+	
+	#[inline(always)]
+	fn call_function_pointer<FixedSizedMessageBody: Sized>(closure: NonNull<()>, variably_sized_message_body: NonNull<VariablySizedMessageBody>, arguments: &MessageHandlerArguments) -> MessageHandlerReturns
+	{
+		let message_handler: fn(&mut FixedSizedMessageBody, &MessageHandlerArguments) -> MessageHandlerReturns = unsafe { transmute(closure.as_ptr()) };
+		message_handler(unsafe { &mut * (variably_sized_message_body.as_ptr() as *mut FixedSizedMessageBody) }, arguments)
+	}
+	
+	#[inline(always)]
+	fn call_closure<FixedSizedMessageBody: Sized, Closure: Fn(&mut FixedSizedMessageBody, &MessageHandlerArguments) -> MessageHandlerReturns>(closure: NonNull<()>, variably_sized_message_body: NonNull<VariablySizedMessageBody>, arguments: &MessageHandlerArguments) -> MessageHandlerReturns
+	{
+		let message_handler = unsafe { & * (closure.as_ptr() as *const Closure) };
+		message_handler(unsafe { &mut * (variably_sized_message_body.as_ptr() as *mut FixedSizedMessageBody) }, arguments)
+	}
+	
+	#[inline(always)]
+	fn drop_function_pointer(_closure: NonNull<()>)
+	{
+	}
+	
+	#[inline(always)]
+	fn drop_boxed_closure<Closure>(closure: NonNull<()>)
+	{
+		drop(unsafe { Box::from_raw(closure.as_ptr() as *mut Closure) })
 	}
 }
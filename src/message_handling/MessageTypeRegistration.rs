@@ -0,0 +1,32 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// Everything registered for a type of message.
+#[derive(Debug)]
+pub(crate) struct MessageTypeRegistration<MessageHandlerArguments, MessageHandlerReturns>
+{
+	pub(crate) message_handler: MessageHandler<MessageHandlerArguments, MessageHandlerReturns>,
+	pub(crate) drop_in_place_function_pointer: DropVariablySizedMessageBodyInPlaceFunctionPointer,
+	pub(crate) type_name: &'static str,
+	pub(crate) type_identifier: TypeId,
+	pub(crate) layout_function_pointer: fn(NonNull<VariablySizedMessageBody>) -> Layout,
+	pub(crate) expired_message_handler: Option<MessageHandler<MessageHandlerArguments, ()>>,
+}
+
+impl<MessageHandlerArguments, MessageHandlerReturns> MessageTypeRegistration<MessageHandlerArguments, MessageHandlerReturns>
+{
+	/// The layout function of a message type which is just a `FixedSizeMessageBody`, ie is not followed by any trailing bytes.
+	#[inline(always)]
+	pub(crate) fn fixed_size_layout<FixedSizeMessageBody: Sized>(_variably_sized_message_body: NonNull<VariablySizedMessageBody>) -> Layout
+	{
+		Layout::new::<FixedSizeMessageBody>()
+	}
+	
+	/// The layout of a message body, including any trailing bytes after it.
+	#[inline(always)]
+	pub(crate) fn layout(&self, variably_sized_message_body: NonNull<VariablySizedMessageBody>) -> Layout
+	{
+		(self.layout_function_pointer)(variably_sized_message_body)
+	}
+}
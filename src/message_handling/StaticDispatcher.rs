@@ -0,0 +1,55 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A function generated by `message_set!` which calls the message handler for a message, then drops the message in place, using a `match` on the compressed type identifier's index rather than an indirect call through `MessageHandler`.
+pub(crate) struct StaticDispatcher<MessageHandlerArguments, MessageHandlerReturns>
+{
+	handle_message_then_drop_in_place: unsafe fn(usize, NonNull<u8>, &MessageHandlerArguments) -> MessageHandlerReturns,
+	number_of_members: usize,
+	message_set_type_identifier: TypeId,
+}
+
+impl<MessageHandlerArguments, MessageHandlerReturns> Debug for StaticDispatcher<MessageHandlerArguments, MessageHandlerReturns>
+{
+	#[inline(always)]
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result
+	{
+		write!(f, "StaticDispatcher({:?})", self.handle_message_then_drop_in_place as usize)
+	}
+}
+
+impl<MessageHandlerArguments, MessageHandlerReturns> StaticDispatcher<MessageHandlerArguments, MessageHandlerReturns>
+{
+	#[inline(always)]
+	pub(crate) fn new(handle_message_then_drop_in_place: unsafe fn(usize, NonNull<u8>, &MessageHandlerArguments) -> MessageHandlerReturns, number_of_members: usize, message_set_type_identifier: TypeId) -> Self
+	{
+		Self
+		{
+			handle_message_then_drop_in_place,
+			number_of_members,
+			message_set_type_identifier,
+		}
+	}
+	
+	/// Was this generated for the `MessageSet` with the type identifier `message_set_type_identifier`?
+	#[inline(always)]
+	pub(crate) fn is_for(&self, message_set_type_identifier: TypeId) -> bool
+	{
+		self.message_set_type_identifier == message_set_type_identifier
+	}
+	
+	/// Message handlers registered after the `MessageSet` are not dispatched.
+	#[inline(always)]
+	pub(crate) fn dispatches(&self, compressed_type_identifier: CompressedTypeIdentifier) -> bool
+	{
+		compressed_type_identifier.index() < self.number_of_members
+	}
+	
+	/// `variably_sized_message_body` must be of the type registered for `compressed_type_identifier`, which must be dispatched.
+	#[inline(always)]
+	pub(crate) fn call_and_drop_in_place(&self, compressed_type_identifier: CompressedTypeIdentifier, variably_sized_message_body: NonNull<VariablySizedMessageBody>, message_handler_arguments: &MessageHandlerArguments) -> MessageHandlerReturns
+	{
+		unsafe { (self.handle_message_then_drop_in_place)(compressed_type_identifier.index(), variably_sized_message_body.cast(), message_handler_arguments) }
+	}
+}
@@ -8,3 +8,5 @@ use super::message::VariablySizedMessageBody;
 
 include!("DropVariablySizedMessageBodyInPlaceFunctionPointer.rs");
 include!("MessageHandler.rs");
+include!("MessageTypeRegistration.rs");
+include!("StaticDispatcher.rs");
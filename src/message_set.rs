@@ -0,0 +1,90 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// Defines a `MessageSet`, `$message_set`, of `Message` types, assigning each a `CompressedTypeIdentifier` in order at compile time.
+///
+/// For example:-
+///
+/// ```ignore
+/// message_set!
+/// {
+/// 	pub ControlPlaneMessages<MyMessageHandlerArguments, MyDequeuedMessageProcessingError>
+/// 	{
+/// 		ReloadConfiguration,
+/// 		InvalidateCache,
+/// 		Shutdown,
+/// 	}
+/// }
+/// ```
+///
+/// Then register with `MessageHandlers::register_message_set::<ControlPlaneMessages>()` and publish using publishers created with `Queues::message_set_publisher::<ControlPlaneMessages, _>()`, so that the compressed type identifiers assigned at compile time are used without a look up.
+///
+/// Each member must implement `Message` by hand; there is no `#[derive(Message)]`, as a derive macro must live in a separate procedural macro crate.
+#[macro_export]
+macro_rules! message_set
+{
+	($visibility: vis $message_set: ident < $message_handler_arguments: ty, $dequeued_message_processing_error: ty > { $($message: ty),* $(,)? }) =>
+	{
+		/// A message set.
+		#[derive(Debug, Copy, Clone)]
+		$visibility struct $message_set;
+		
+		impl $crate::MessageSet for $message_set
+		{
+			type MessageHandlerArguments = $message_handler_arguments;
+			
+			type DequeuedMessageProcessingError = $dequeued_message_processing_error;
+			
+			#[inline(always)]
+			fn register_message_handlers(message_handlers: &mut $crate::MessageHandlers<Self::MessageHandlerArguments, ::std::result::Result<(), Self::DequeuedMessageProcessingError>>)
+			{
+				$(
+					message_handlers.register_message_set_member::<Self, $message>();
+				)*
+			}
+			
+			#[inline(always)]
+			unsafe fn handle_message_then_drop_in_place(compressed_type_identifier_index: usize, message_body: ::std::ptr::NonNull<u8>, message_handler_arguments: &Self::MessageHandlerArguments) -> ::std::result::Result<(), Self::DequeuedMessageProcessingError>
+			{
+				$crate::message_set!(@handle_message_then_drop_in_place compressed_type_identifier_index, message_body, message_handler_arguments, 0usize; $($message),*)
+			}
+		}
+		
+		$crate::message_set!(@message_set_member $message_set, 0usize; $($message),*);
+	};
+	
+	(@handle_message_then_drop_in_place $compressed_type_identifier_index: ident, $message_body: ident, $message_handler_arguments: ident, $index: expr; ) =>
+	{
+		unreachable!("Compressed type identifier index `{}` is not in the message set", $compressed_type_identifier_index)
+	};
+	
+	(@handle_message_then_drop_in_place $compressed_type_identifier_index: ident, $message_body: ident, $message_handler_arguments: ident, $index: expr; $message: ty $(, $remaining_message: ty)*) =>
+	{
+		if $compressed_type_identifier_index == $index
+		{
+			let message = &mut * ($message_body.as_ptr() as *mut $message);
+			let result = <$message as $crate::Message>::handle_message(message, $message_handler_arguments);
+			::std::ptr::drop_in_place(message);
+			result
+		}
+		else
+		{
+			$crate::message_set!(@handle_message_then_drop_in_place $compressed_type_identifier_index, $message_body, $message_handler_arguments, $index + 1usize; $($remaining_message),*)
+		}
+	};
+	
+	(@message_set_member $message_set: ident, $index: expr; ) =>
+	{
+	};
+	
+	(@message_set_member $message_set: ident, $index: expr; $message: ty $(, $remaining_message: ty)*) =>
+	{
+		impl $crate::MessageSetMember<$message_set> for $message
+		{
+			const CompressedTypeIdentifierIndex: usize = $index;
+		}
+		
+		$crate::message_set!(@message_set_member $message_set, $index + 1usize; $($remaining_message),*);
+	};
+}
@@ -0,0 +1,38 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// Reads the time stamp counter (TSC) or its equivalent.
+///
+/// Cheap (no system call) and monotonic on a particular hyper thread; not suitable for comparisons across hyper threads or sockets.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn read_time_stamp_counter() -> u64
+{
+	unsafe { _rdtsc() }
+}
+
+/// Reads the time stamp counter (TSC) or its equivalent.
+///
+/// Cheap (no system call) and monotonic on a particular hyper thread; not suitable for comparisons across hyper threads or sockets.
+#[cfg(target_arch = "aarch64")]
+#[inline(always)]
+fn read_time_stamp_counter() -> u64
+{
+	let virtual_count: u64;
+	unsafe { asm!("mrs {}, cntvct_el0", out(reg) virtual_count, options(nomem, nostack)) };
+	virtual_count
+}
+
+/// Reads the raw monotonic clock in nanoseconds, for architectures without a time stamp counter (TSC) known to this crate.
+///
+/// Usually cheap (the vDSO avoids a system call) and not subject to NTP adjustment.
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+#[inline(always)]
+fn read_time_stamp_counter() -> u64
+{
+	let mut time = unsafe_uninitialized::<timespec>();
+	let result = unsafe { clock_gettime(CLOCK_MONOTONIC_RAW, &mut time) };
+	debug_assert_eq!(result, 0, "clock_gettime(CLOCK_MONOTONIC_RAW) failed");
+	(time.tv_sec as u64) * 1_000_000_000 + (time.tv_nsec as u64)
+}
@@ -0,0 +1,65 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A message whose body is aligned to `align_of::<Alignment>()` and whose message handler records its `value` and address if, and only if, it is correctly aligned.
+#[derive(Debug)]
+#[repr(C)]
+struct AlignedMessage<Alignment: 'static>
+{
+	_alignment: [Alignment; 0],
+	value: u64,
+	handled_values: Rc<RefCell<Vec<(u64, usize)>>>,
+}
+
+impl<Alignment: 'static> AlignedMessage<Alignment>
+{
+	#[inline(always)]
+	fn register(message_handlers: &mut MessageHandlers<(), Result<(), TestError>>) -> CompressedTypeIdentifier
+	{
+		message_handlers.register_value_message_handler::<Self>(Self::handle_message)
+	}
+	
+	#[inline(always)]
+	fn handle_message(&mut self, _message_handler_arguments: &()) -> Result<(), TestError>
+	{
+		let address = self as *const Self as usize;
+		if address % align_of::<Self>() != 0
+		{
+			return Err(TestError)
+		}
+		
+		self.handled_values.borrow_mut().push((self.value, address));
+		Ok(())
+	}
+	
+	/// Enqueues a message for each of `values` and returns the values (and addresses) handled so far, to which they will be added when they are.
+	#[inline(always)]
+	fn enqueue_all(queue: &Queue<(), TestError>, compressed_type_identifier: CompressedTypeIdentifier, values: Range<u64>) -> Rc<RefCell<Vec<(u64, usize)>>>
+	{
+		let handled_values = Rc::new(RefCell::new(Vec::new()));
+		for value in values
+		{
+			let message = Self
+			{
+				_alignment: [],
+				value,
+				handled_values: handled_values.clone(),
+			};
+			unsafe { queue.enqueue(compressed_type_identifier, |uninitialized_memory| write(uninitialized_memory.as_ptr(), message)) }
+		}
+		handled_values
+	}
+}
+
+#[repr(align(32))]
+#[derive(Debug)]
+struct Align32;
+
+#[repr(align(64))]
+#[derive(Debug)]
+struct Align64;
+
+#[repr(align(4096))]
+#[derive(Debug)]
+struct Align4096;
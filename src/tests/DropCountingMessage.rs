@@ -0,0 +1,75 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A message which counts how many times it has been dropped and whose message handler fails if asked to.
+#[derive(Debug)]
+struct DropCountingMessage
+{
+	drops: Rc<Cell<usize>>,
+	message_handler_fails: bool,
+}
+
+impl Drop for DropCountingMessage
+{
+	#[inline(always)]
+	fn drop(&mut self)
+	{
+		self.drops.set(self.drops.get() + 1)
+	}
+}
+
+impl DropCountingMessage
+{
+	/// Returns the message and its count of drops.
+	#[inline(always)]
+	fn new(message_handler_fails: bool) -> (Self, Rc<Cell<usize>>)
+	{
+		let drops = Rc::new(Cell::new(0));
+		(
+			Self
+			{
+				drops: drops.clone(),
+				message_handler_fails,
+			},
+			drops,
+		)
+	}
+	
+	#[inline(always)]
+	fn register(message_handlers: &mut MessageHandlers<(), Result<(), TestError>>) -> CompressedTypeIdentifier
+	{
+		message_handlers.register_value_message_handler::<Self>(Self::handle_message)
+	}
+	
+	#[inline(always)]
+	fn handle_message(&mut self, _message_handler_arguments: &()) -> Result<(), TestError>
+	{
+		if self.message_handler_fails
+		{
+			Err(TestError)
+		}
+		else
+		{
+			Ok(())
+		}
+	}
+	
+	/// Enqueues a message for each of `message_handler_fails` and returns their counts of drops.
+	#[inline(always)]
+	fn enqueue_all(queue: &Queue<(), TestError>, compressed_type_identifier: CompressedTypeIdentifier, message_handler_fails: &[bool]) -> Vec<Rc<Cell<usize>>>
+	{
+		message_handler_fails.iter().map(|&message_handler_fails|
+		{
+			let (message, drops) = Self::new(message_handler_fails);
+			unsafe { queue.enqueue(compressed_type_identifier, |uninitialized_memory| write(uninitialized_memory.as_ptr(), message)) };
+			drops
+		}).collect()
+	}
+	
+	#[inline(always)]
+	fn drops(drops: &[Rc<Cell<usize>>]) -> Vec<usize>
+	{
+		drops.iter().map(|drops| drops.get()).collect()
+	}
+}
@@ -0,0 +1,73 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A queue with a compact lane of `compact_lane_maximum_number_of_messages` and a compact message handler for `Box<u64>` which records each value and opcode handled.
+#[inline(always)]
+fn new_queue_with_compact_lane(overflow_policy: OverflowPolicy, compact_lane_maximum_number_of_messages: u64) -> (Queue<(), TestError>, Option<CompressedTypeIdentifier>, Arc<Mutex<Vec<(u64, u32)>>>)
+{
+	let handled = Arc::new(Mutex::new(Vec::new()));
+	
+	let mut queue_settings = test_queue_settings();
+	queue_settings.overflow_policy = overflow_policy;
+	queue_settings.compact_lane_maximum_number_of_messages = Some(new_non_zero_u64(compact_lane_maximum_number_of_messages));
+	
+	let handled_by_message_handler = handled.clone();
+	let (queue, compressed_type_identifier) = new_queue
+	(
+		queue_settings,
+		move |message_handlers| message_handlers.register_compact_message_handler::<u64>
+		(
+			move |boxed, opcode, _message_handler_arguments|
+			{
+				handled_by_message_handler.lock().unwrap().push((*boxed, opcode));
+				Ok(())
+			}
+		)
+	);
+	assert!(queue.compact_lane.is_some());
+	
+	(queue, Some(compressed_type_identifier), handled)
+}
+
+#[test]
+fn compact_messages_are_handed_off_with_their_opcode()
+{
+	let (queue, compressed_type_identifier, handled) = new_queue_with_compact_lane(OverflowPolicy::default(), 16);
+	
+	unsafe { queue.enqueue_compact(compressed_type_identifier, Priority::Highest, Box::new(1), 0) };
+	unsafe { queue.enqueue_compact(compressed_type_identifier, Priority::Highest, Box::new(2), CompactPublisher::<u64, (), TestError>::MaximumOpcode) };
+	
+	dequeue_all(&queue, &mut DequeuedMessageProcessingErrorPolicy::StopAndLeaveRemaining).expect("Compact message handler never fails");
+	assert_eq!(*handled.lock().unwrap(), vec![(1, 0), (2, CompactPublisher::<u64, (), TestError>::MaximumOpcode)]);
+}
+
+#[test]
+fn compact_messages_spilled_to_the_heap_are_first_in_first_out()
+{
+	let (queue, compressed_type_identifier, handled) = new_queue_with_compact_lane(OverflowPolicy::SpillToHeap, 2);
+	
+	for value in 0 .. 10
+	{
+		unsafe { queue.enqueue_compact(compressed_type_identifier, Priority::Highest, Box::new(value), value as u32) };
+	}
+	assert_ne!(queue.overflow_counters().spilled_to_heap(), 0);
+	
+	dequeue_all(&queue, &mut DequeuedMessageProcessingErrorPolicy::StopAndLeaveRemaining).expect("Compact message handler never fails");
+	assert_eq!(*handled.lock().unwrap(), (0 .. 10).map(|value| (value, value as u32)).collect::<Vec<_>>());
+}
+
+#[test]
+fn compact_messages_are_dropped_newest_when_the_compact_lane_is_full()
+{
+	let (queue, compressed_type_identifier, handled) = new_queue_with_compact_lane(OverflowPolicy::DropNewest, 2);
+	
+	for value in 0 .. 3
+	{
+		unsafe { queue.enqueue_compact(compressed_type_identifier, Priority::Highest, Box::new(value), 0) };
+	}
+	assert_eq!(queue.overflow_counters().dropped_newest(), 1);
+	
+	dequeue_all(&queue, &mut DequeuedMessageProcessingErrorPolicy::StopAndLeaveRemaining).expect("Compact message handler never fails");
+	assert_eq!(*handled.lock().unwrap(), vec![(0, 0), (1, 0)]);
+}
@@ -0,0 +1,83 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+#[test]
+fn stop_and_leave_remaining_drops_the_failed_message_exactly_once()
+{
+	let (queue, compressed_type_identifier) = new_queue(test_queue_settings(), DropCountingMessage::register);
+	let drops = DropCountingMessage::enqueue_all(&queue, compressed_type_identifier, &[false, true, false]);
+	
+	let dequeue_failed = dequeue_all(&queue, &mut DequeuedMessageProcessingErrorPolicy::StopAndLeaveRemaining).expect_err("Second message handler fails");
+	assert_eq!(dequeue_failed.dequeued_message_processing_error, TestError);
+	assert_eq!(dequeue_failed.dequeue_outcome.messages_dequeued, 1);
+	assert_eq!(dequeue_failed.dequeue_outcome.stopped_because, DequeueStoppedBecause::MessageHandlerFailed);
+	assert_eq!(DropCountingMessage::drops(&drops), [1, 1, 0]);
+	
+	let dequeue_outcome = dequeue_all(&queue, &mut DequeuedMessageProcessingErrorPolicy::StopAndLeaveRemaining).expect("Remaining message handler succeeds");
+	assert_eq!(dequeue_outcome.messages_dequeued, 1);
+	assert_eq!(DropCountingMessage::drops(&drops), [1, 1, 1]);
+	
+	drop(queue);
+	assert_eq!(DropCountingMessage::drops(&drops), [1, 1, 1]);
+}
+
+#[test]
+fn stop_and_leave_remaining_leaves_remaining_messages_to_be_dropped_with_the_queue()
+{
+	let (queue, compressed_type_identifier) = new_queue(test_queue_settings(), DropCountingMessage::register);
+	let drops = DropCountingMessage::enqueue_all(&queue, compressed_type_identifier, &[true, false]);
+	
+	dequeue_all(&queue, &mut DequeuedMessageProcessingErrorPolicy::StopAndLeaveRemaining).expect_err("First message handler fails");
+	assert_eq!(DropCountingMessage::drops(&drops), [1, 0]);
+	
+	drop(queue);
+	assert_eq!(DropCountingMessage::drops(&drops), [1, 1]);
+}
+
+#[test]
+fn continue_and_collect_errors_below_maximum_errors_drops_each_failed_message_exactly_once()
+{
+	let (queue, compressed_type_identifier) = new_queue(test_queue_settings(), DropCountingMessage::register);
+	let drops = DropCountingMessage::enqueue_all(&queue, compressed_type_identifier, &[false, true, true, false]);
+	
+	let mut collected_errors = Vec::new();
+	let dequeue_outcome = dequeue_all(&queue, &mut DequeuedMessageProcessingErrorPolicy::ContinueAndCollectErrors { collected_errors: &mut collected_errors, maximum_errors: 3 }).expect("Errors are collected");
+	assert_eq!(dequeue_outcome.messages_dequeued, 4);
+	assert_eq!(dequeue_outcome.message_handlers_failed, 2);
+	assert_eq!(dequeue_outcome.stopped_because, DequeueStoppedBecause::QueueExhausted);
+	assert_eq!(collected_errors, [(compressed_type_identifier, TestError), (compressed_type_identifier, TestError)]);
+	assert_eq!(DropCountingMessage::drops(&drops), [1, 1, 1, 1]);
+}
+
+#[test]
+fn continue_and_collect_errors_at_maximum_errors_stops_and_drops_the_failed_message_exactly_once()
+{
+	let (queue, compressed_type_identifier) = new_queue(test_queue_settings(), DropCountingMessage::register);
+	let drops = DropCountingMessage::enqueue_all(&queue, compressed_type_identifier, &[true, true, false]);
+	
+	let mut collected_errors = Vec::new();
+	let dequeue_failed = dequeue_all(&queue, &mut DequeuedMessageProcessingErrorPolicy::ContinueAndCollectErrors { collected_errors: &mut collected_errors, maximum_errors: 1 }).expect_err("Second error exceeds maximum_errors");
+	assert_eq!(dequeue_failed.dequeue_outcome.messages_dequeued, 1);
+	assert_eq!(dequeue_failed.dequeue_outcome.message_handlers_failed, 1);
+	assert_eq!(collected_errors.len(), 1);
+	assert_eq!(DropCountingMessage::drops(&drops), [1, 1, 0]);
+	
+	drop(queue);
+	assert_eq!(DropCountingMessage::drops(&drops), [1, 1, 1]);
+}
+
+#[test]
+fn callback_is_called_for_each_failed_message_which_is_dropped_exactly_once()
+{
+	let (queue, compressed_type_identifier) = new_queue(test_queue_settings(), DropCountingMessage::register);
+	let drops = DropCountingMessage::enqueue_all(&queue, compressed_type_identifier, &[true, false, true]);
+	
+	let mut failed_compressed_type_identifiers = Vec::new();
+	let mut callback = |compressed_type_identifier: CompressedTypeIdentifier, _dequeued_message_processing_error: TestError| failed_compressed_type_identifiers.push(compressed_type_identifier);
+	let dequeue_outcome = dequeue_all(&queue, &mut DequeuedMessageProcessingErrorPolicy::Callback(&mut callback)).expect("Callback continues");
+	assert_eq!(dequeue_outcome.messages_dequeued, 3);
+	assert_eq!(dequeue_outcome.message_handlers_failed, 2);
+	assert_eq!(failed_compressed_type_identifiers, [compressed_type_identifier, compressed_type_identifier]);
+	assert_eq!(DropCountingMessage::drops(&drops), [1, 1, 1]);
+}
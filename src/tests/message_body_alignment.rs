@@ -0,0 +1,80 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// Enqueues `values`, dequeues all of them and checks every message body was correctly aligned when handled.
+///
+/// Returns the address of each message body when handled.
+#[inline(always)]
+fn round_trip_batch<Alignment: 'static>(queue: &Queue<(), TestError>, compressed_type_identifier: CompressedTypeIdentifier, values: Range<u64>) -> Vec<usize>
+{
+	let handled_values = AlignedMessage::<Alignment>::enqueue_all(queue, compressed_type_identifier, values.clone());
+	
+	let dequeue_outcome = dequeue_all(queue, &mut DequeuedMessageProcessingErrorPolicy::StopAndLeaveRemaining).expect("Every message body is correctly aligned");
+	assert_eq!(dequeue_outcome.messages_dequeued, values.end - values.start);
+	
+	let handled_values = handled_values.borrow();
+	assert_eq!(handled_values.iter().map(|&(value, _address)| value).collect::<Vec<_>>(), values.collect::<Vec<_>>());
+	handled_values.iter().map(|&(_value, address)| address).collect()
+}
+
+/// Round trips batches of `BatchSize` messages until they have wrapped around the end of the magic ring buffer `NumberOfWraps` times.
+///
+/// The magic ring buffer is a whole number of pages, which may be huge pages, so it can be far larger than the queue size; counting wraps, rather than messages, ensures messages wrap whatever page size is actually used.
+#[inline(always)]
+fn round_trip_until_wrapped<Alignment: 'static>()
+{
+	let (queue, compressed_type_identifier) = new_queue(test_queue_settings(), AlignedMessage::<Alignment>::register);
+	
+	let mut number_of_wraps = 0;
+	let mut previous_address = 0;
+	let mut first_value = 0;
+	while number_of_wraps < NumberOfWraps
+	{
+		for address in round_trip_batch::<Alignment>(&queue, compressed_type_identifier, first_value .. (first_value + BatchSize))
+		{
+			// A message is read from the start of the magic ring buffer after it wraps.
+			if address < previous_address
+			{
+				number_of_wraps += 1
+			}
+			previous_address = address;
+		}
+		first_value += BatchSize;
+	}
+}
+
+/// The number of times messages must wrap around the end of the magic ring buffer.
+const NumberOfWraps: u64 = 3;
+
+/// Fewer messages than fit in a queue with `test_queue_settings()`, so that enqueuing a batch never blocks.
+const BatchSize: u64 = 10;
+
+#[test]
+fn message_bodies_aligned_to_32_bytes_round_trip()
+{
+	round_trip_until_wrapped::<Align32>();
+}
+
+#[test]
+fn message_bodies_aligned_to_64_bytes_round_trip()
+{
+	round_trip_until_wrapped::<Align64>();
+}
+
+#[test]
+fn message_bodies_aligned_to_a_page_round_trip()
+{
+	round_trip_until_wrapped::<Align4096>();
+}
+
+#[test]
+fn message_bodies_aligned_to_a_page_round_trip_when_spilled_to_the_heap()
+{
+	let mut queue_settings = QueueSettings::new(new_non_zero_u64(1));
+	queue_settings.overflow_policy = OverflowPolicy::SpillToHeap;
+	
+	let (queue, compressed_type_identifier) = new_queue(queue_settings, AlignedMessage::<Align4096>::register);
+	round_trip_batch::<Align4096>(&queue, compressed_type_identifier, 0 .. 100);
+	assert_ne!(queue.overflow_counters().spilled_to_heap(), 0);
+}
@@ -0,0 +1,14 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+use super::*;
+
+
+include!("AlignedMessage.rs");
+include!("compact_lane.rs");
+include!("dequeued_message_processing_error_policy.rs");
+include!("DropCountingMessage.rs");
+include!("message_body_alignment.rs");
+include!("new_queue.rs");
+include!("TestError.rs");
@@ -0,0 +1,28 @@
+// This file is part of message-dispatch. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT. No part of message-dispatch, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019-2020 The developers of message-dispatch. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/message-dispatch/master/COPYRIGHT.
+
+
+/// A queue, with one priority lane per `queue_settings`, using message handlers registered by `register`.
+#[inline(always)]
+fn new_queue<R>(queue_settings: QueueSettings, register: impl FnOnce(&mut MessageHandlers<(), Result<(), TestError>>) -> R) -> (Queue<(), TestError>, R)
+{
+	let mut message_handlers = MessageHandlers::default();
+	let registered = register(&mut message_handlers);
+	
+	let defaults = DefaultHugePageSizes::new(&SysPath::default(), &ProcPath::default());
+	let queue_size_in_bytes = message_handlers.queue_size_in_bytes(queue_settings.preferred_maximum_number_of_elements_of_largest_possible_fixed_size_message_body);
+	let queue = Queue::new(message_handlers, &defaults, queue_size_in_bytes, 0, &queue_settings).expect("Could not create queue");
+	(queue, registered)
+}
+
+#[inline(always)]
+fn dequeue_all(queue: &Queue<(), TestError>, dequeued_message_processing_error_policy: &mut DequeuedMessageProcessingErrorPolicy<TestError>) -> Result<DequeueOutcome, DequeueFailed<TestError>>
+{
+	queue.dequeue(&Arc::new(SimpleTerminate::default()), &(), &DequeueBudget::Unlimited, dequeued_message_processing_error_policy)
+}
+
+#[inline(always)]
+fn test_queue_settings() -> QueueSettings
+{
+	QueueSettings::new(new_non_zero_u64(16))
+}
@@ -19,15 +19,16 @@
 /// * On all systems, a virtual method table can never be the null pointer (all zeros).
 /// * However, 52-bit pointers are proposed for ARMv8.2 and 56-bit pointers by Intel for x86-64 (<https://software.intel.com/sites/default/files/managed/2b/80/5-level_paging_white_paper.pdf>), but these features are a way off and will require opt-in by Linux.
 #[derive(Default, Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
-#[allow(dead_code)]
 pub(crate) struct TaggedNonNullPointer(u64);
 
-#[allow(dead_code)]
 impl TaggedNonNullPointer
 {
 	#[cfg(target_pointer_width = "64")] const AlignedPointerShift: u64 = 3;
 	#[cfg(target_pointer_width = "32")] const AlignedPointerShift: u64 = 2;
 
+	/// Pointers must be aligned to at least this.
+	pub(crate) const MinimumPointerAlignment: usize = 1 << Self::AlignedPointerShift;
+
 	#[cfg(target_pointer_width = "64")] const PointerOverheadInBitsWhen64BitEncoded: u64 = 16 + Self::AlignedPointerShift;
 	#[cfg(target_pointer_width = "32")] const PointerOverheadInBitsWhen64BitEncoded: u64 = 32 + Self::AlignedPointerShift;
 
@@ -50,6 +51,7 @@ impl TaggedNonNullPointer
 	///
 	/// Tag bits must not exceed `2^19 - 1` to be platform-portable.
 	#[inline(always)]
+	#[allow(dead_code)]
 	pub fn special(tag_bits: u32) -> Self
 	{
 		Self::new(tag_bits, null_mut())
@@ -36,6 +36,29 @@ impl VirtualMethodTablePointer
 		this
 	}
 
+	/// Obtains the virtual method table (vtable) of `Value` when used as a `Trait` object, eg `dyn Trait`.
+	#[inline(always)]
+	pub fn from_unsize<Value: Unsize<Trait>, Trait: ?Sized>(value: &Value) -> Self
+	{
+		let fat_pointer: &Trait = value;
+		let trait_object: TraitObject = unsafe { transmute_copy(&fat_pointer) };
+		Self(new_non_null(trait_object.vtable))
+	}
+	
+	/// Reconstructs a `Trait` object, eg `dyn Trait`, from a pointer to its data.
+	///
+	/// `data` must point to an instance of the type this virtual method table was obtained for.
+	#[inline(always)]
+	pub fn trait_object<Trait: ?Sized>(self, data: NonNull<()>) -> NonNull<Trait>
+	{
+		let trait_object = TraitObject
+		{
+			data: data.as_ptr(),
+			vtable: self.0.as_ptr(),
+		};
+		unsafe { transmute_copy(&trait_object) }
+	}
+
 	/// The function pointer to drop an instance in place.
 	#[inline(always)]
 	pub fn drop_in_place_function_pointer(self) -> DropInPlaceFunctionPointer
@@ -46,18 +69,16 @@ impl VirtualMethodTablePointer
 
 	/// Size.
 	#[inline(always)]
-	#[allow(dead_code)]
 	pub fn size(self) -> usize
 	{
-		unsafe { *(self.0.as_ptr().add(1) as *mut usize) }
+		unsafe { *(self.0.as_ptr() as *const usize).add(1) }
 	}
 
 	/// Alignment.
 	#[inline(always)]
-	#[allow(dead_code)]
 	pub fn alignment(self) -> usize
 	{
-		unsafe { *(self.0.as_ptr().add(2) as *mut usize) }
+		unsafe { *(self.0.as_ptr() as *const usize).add(2) }
 	}
 
 	/// A pointer to the first function in the trait definition.
@@ -65,7 +86,7 @@ impl VirtualMethodTablePointer
 	#[allow(dead_code)]
 	pub fn first_trait_function_pointer(self) -> usize
 	{
-		let raw_pointer = unsafe { *(self.0.as_ptr().add(3) as *mut usize) };
+		let raw_pointer = unsafe { *(self.0.as_ptr() as *const usize).add(3) };
 		raw_pointer
 	}
 }